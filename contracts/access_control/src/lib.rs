@@ -0,0 +1,318 @@
+#![no_std]
+// Role-based access control shared by the game contracts. There is one admin,
+// handed over in two steps so a typo can't lock a contract, plus any number of
+// Symbol-named roles. Each role is managed by the admin and, optionally, by the
+// holders of an admin role set with `set_role_admin`.
+//
+// This crate, pausable and timelock keep their keys in the host contract's
+// instance storage next to its own DataKey, so no variant name may clash with it.
+
+use soroban_sdk::{contractevent, contracttype, symbol_short, Address, Env, Symbol};
+
+// Roles used across the workspace
+pub const GUARDIAN: Symbol = symbol_short!("guardian"); // May pause features
+pub const OPERATOR: Symbol = symbol_short!("operator"); // May move yield between vault users
+pub const GAME: Symbol = symbol_short!("game");         // Game contract trusted by YieldEngine
+
+#[contracttype]
+#[derive(Clone)]
+pub enum AcKey {
+    Admin,
+    PendingAdmin,           // Nominated by the admin, must accept
+    Role(Symbol, Address),  // (Role, Account) -> true
+    RoleAdmin(Symbol),      // Role -> Role whose holders may grant and revoke it
+}
+
+#[contractevent(data_format = "single-value")]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct RoleGranted {
+    #[topic]
+    pub role: Symbol,
+    #[topic]
+    pub account: Address,
+    pub by: Address,
+}
+
+#[contractevent(data_format = "single-value")]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct RoleRevoked {
+    #[topic]
+    pub role: Symbol,
+    #[topic]
+    pub account: Address,
+    pub by: Address,
+}
+
+#[contractevent(data_format = "single-value")]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct AdminTransferStarted {
+    #[topic]
+    pub admin: Address,
+    pub pending: Address,
+}
+
+#[contractevent(data_format = "single-value")]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct AdminTransferred {
+    #[topic]
+    pub previous: Address,
+    pub admin: Address,
+}
+
+pub fn has_admin(env: &Env) -> bool {
+    env.storage().instance().has(&AcKey::Admin)
+}
+
+pub fn init(env: &Env, admin: &Address) {
+    if has_admin(env) {
+        panic!("Admin already set");
+    }
+    env.storage().instance().set(&AcKey::Admin, admin);
+}
+
+pub fn admin(env: &Env) -> Address {
+    env.storage().instance().get(&AcKey::Admin).expect("Admin not set")
+}
+
+pub fn require_admin(env: &Env) -> Address {
+    let admin = admin(env);
+    admin.require_auth();
+    admin
+}
+
+pub fn pending_admin(env: &Env) -> Option<Address> {
+    env.storage().instance().get(&AcKey::PendingAdmin)
+}
+
+// Step one: the admin nominates a successor, who keeps no power until accepting
+pub fn transfer_admin(env: &Env, new_admin: &Address) {
+    let admin = require_admin(env);
+    env.storage().instance().set(&AcKey::PendingAdmin, new_admin);
+    AdminTransferStarted { admin, pending: new_admin.clone() }.publish(env);
+}
+
+// Step two: the nominee signs to take over
+pub fn accept_admin(env: &Env) {
+    let pending = pending_admin(env).expect("No pending admin");
+    pending.require_auth();
+    let previous = admin(env);
+    env.storage().instance().set(&AcKey::Admin, &pending);
+    env.storage().instance().remove(&AcKey::PendingAdmin);
+    AdminTransferred { previous, admin: pending }.publish(env);
+}
+
+pub fn has_role(env: &Env, role: &Symbol, account: &Address) -> bool {
+    env.storage().instance().get(&AcKey::Role(role.clone(), account.clone())).unwrap_or(false)
+}
+
+// `account` signs and must hold `role`
+pub fn require_role(env: &Env, role: &Symbol, account: &Address) {
+    account.require_auth();
+    if !has_role(env, role, account) {
+        panic!("Missing role");
+    }
+}
+
+pub fn role_admin(env: &Env, role: &Symbol) -> Option<Symbol> {
+    env.storage().instance().get(&AcKey::RoleAdmin(role.clone()))
+}
+
+pub fn set_role_admin(env: &Env, role: &Symbol, admin_role: &Symbol) {
+    require_admin(env);
+    env.storage().instance().set(&AcKey::RoleAdmin(role.clone()), admin_role);
+}
+
+// The admin manages every role, holders of the role admin manage theirs
+fn require_role_manager(env: &Env, caller: &Address, role: &Symbol) {
+    caller.require_auth();
+    if *caller == admin(env) {
+        return;
+    }
+    match role_admin(env, role) {
+        Some(admin_role) if has_role(env, &admin_role, caller) => {}
+        _ => panic!("Caller cannot manage role"),
+    }
+}
+
+// Roles that can move player funds, so granting them has to wait out the
+// host's timelock like any other parameter change
+pub fn is_timelocked(role: &Symbol) -> bool {
+    *role == OPERATOR || *role == GAME
+}
+
+fn set_role(env: &Env, role: &Symbol, account: &Address, by: &Address) {
+    env.storage().instance().set(&AcKey::Role(role.clone(), account.clone()), &true);
+    RoleGranted { role: role.clone(), account: account.clone(), by: by.clone() }.publish(env);
+}
+
+pub fn grant_role(env: &Env, caller: &Address, role: &Symbol, account: &Address) {
+    require_role_manager(env, caller, role);
+    if is_timelocked(role) {
+        panic!("Role must be granted through the timelock");
+    }
+    set_role(env, role, account, caller);
+}
+
+// Grants a timelocked role, for the host to call when the queued grant executes
+pub fn grant_queued_role(env: &Env, role: &Symbol, account: &Address) {
+    set_role(env, role, account, &admin(env));
+}
+
+pub fn revoke_role(env: &Env, caller: &Address, role: &Symbol, account: &Address) {
+    require_role_manager(env, caller, role);
+    env.storage().instance().remove(&AcKey::Role(role.clone(), account.clone()));
+    RoleRevoked { role: role.clone(), account: account.clone(), by: caller.clone() }.publish(env);
+}
+
+pub fn renounce_role(env: &Env, account: &Address, role: &Symbol) {
+    account.require_auth();
+    env.storage().instance().remove(&AcKey::Role(role.clone(), account.clone()));
+    RoleRevoked { role: role.clone(), account: account.clone(), by: account.clone() }.publish(env);
+}
+
+// Role and admin entrypoints for a host contract, generated here so every
+// contract exposes the same interface:
+//
+//     access_control::role_entrypoints!(CitadelVault);
+#[macro_export]
+macro_rules! role_entrypoints {
+    ($contract:ident) => {
+        #[soroban_sdk::contractimpl]
+        impl $contract {
+            // Admin, or holders of the role's admin role: give `account` a role
+            pub fn grant_role(env: soroban_sdk::Env, caller: soroban_sdk::Address, role: soroban_sdk::Symbol, account: soroban_sdk::Address) {
+                $crate::grant_role(&env, &caller, &role, &account);
+            }
+
+            pub fn revoke_role(env: soroban_sdk::Env, caller: soroban_sdk::Address, role: soroban_sdk::Symbol, account: soroban_sdk::Address) {
+                $crate::revoke_role(&env, &caller, &role, &account);
+            }
+
+            pub fn renounce_role(env: soroban_sdk::Env, account: soroban_sdk::Address, role: soroban_sdk::Symbol) {
+                $crate::renounce_role(&env, &account, &role);
+            }
+
+            pub fn has_role(env: soroban_sdk::Env, role: soroban_sdk::Symbol, account: soroban_sdk::Address) -> bool {
+                $crate::has_role(&env, &role, &account)
+            }
+
+            // Admin: let holders of `admin_role` grant and revoke `role`
+            pub fn set_role_admin(env: soroban_sdk::Env, role: soroban_sdk::Symbol, admin_role: soroban_sdk::Symbol) {
+                $crate::set_role_admin(&env, &role, &admin_role);
+            }
+
+            // Admin: nominate a successor, who takes over once they call `accept_admin`
+            pub fn transfer_admin(env: soroban_sdk::Env, new_admin: soroban_sdk::Address) {
+                $crate::transfer_admin(&env, &new_admin);
+            }
+
+            pub fn accept_admin(env: soroban_sdk::Env) {
+                $crate::accept_admin(&env);
+            }
+
+            pub fn admin(env: soroban_sdk::Env) -> soroban_sdk::Address {
+                $crate::admin(&env)
+            }
+        }
+    };
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use soroban_sdk::{contract, testutils::Address as _};
+
+    #[contract]
+    struct Host;
+
+    role_entrypoints!(Host);
+
+    fn setup() -> (Env, Address, Address) {
+        let env = Env::default();
+        env.mock_all_auths();
+        let host = env.register(Host, ());
+        let admin = Address::generate(&env);
+        env.as_contract(&host, || init(&env, &admin));
+        (env, host, admin)
+    }
+
+    #[test]
+    fn admin_grants_and_revokes() {
+        let (env, host, admin) = setup();
+        let guardian = Address::generate(&env);
+
+        env.as_contract(&host, || grant_role(&env, &admin, &GUARDIAN, &guardian));
+        env.as_contract(&host, || {
+            assert!(has_role(&env, &GUARDIAN, &guardian));
+            assert!(!has_role(&env, &OPERATOR, &guardian));
+        });
+        env.as_contract(&host, || revoke_role(&env, &admin, &GUARDIAN, &guardian));
+        env.as_contract(&host, || assert!(!has_role(&env, &GUARDIAN, &guardian)));
+    }
+
+    #[test]
+    #[should_panic(expected = "Role must be granted through the timelock")]
+    fn fund_moving_roles_cannot_be_granted_directly() {
+        let (env, host, admin) = setup();
+        let operator = Address::generate(&env);
+
+        env.as_contract(&host, || grant_role(&env, &admin, &OPERATOR, &operator));
+    }
+
+    #[test]
+    fn role_admins_manage_their_role() {
+        let (env, host, admin) = setup();
+        let lead = Address::generate(&env);
+        let guardian = Address::generate(&env);
+        let lead_role = symbol_short!("lead");
+
+        env.as_contract(&host, || set_role_admin(&env, &GUARDIAN, &lead_role));
+        env.as_contract(&host, || grant_role(&env, &admin, &lead_role, &lead));
+        env.as_contract(&host, || grant_role(&env, &lead, &GUARDIAN, &guardian));
+        env.as_contract(&host, || assert!(has_role(&env, &GUARDIAN, &guardian)));
+    }
+
+    #[test]
+    #[should_panic(expected = "Caller cannot manage role")]
+    fn others_cannot_grant() {
+        let (env, host, _) = setup();
+        let stranger = Address::generate(&env);
+
+        env.as_contract(&host, || grant_role(&env, &stranger, &GUARDIAN, &stranger));
+    }
+
+    #[test]
+    fn admin_transfer_takes_two_steps() {
+        let (env, host, admin) = setup();
+        let successor = Address::generate(&env);
+
+        env.as_contract(&host, || transfer_admin(&env, &successor));
+        env.as_contract(&host, || {
+            assert_eq!(super::admin(&env), admin);
+            assert_eq!(pending_admin(&env), Some(successor.clone()));
+        });
+        env.as_contract(&host, || accept_admin(&env));
+        env.as_contract(&host, || {
+            assert_eq!(super::admin(&env), successor);
+            assert_eq!(pending_admin(&env), None);
+        });
+    }
+
+    #[test]
+    #[should_panic(expected = "No pending admin")]
+    fn accept_needs_a_nomination() {
+        let (env, host, _) = setup();
+        env.as_contract(&host, || accept_admin(&env));
+    }
+
+    #[test]
+    fn entrypoints_reach_the_module() {
+        let (env, host, admin) = setup();
+        let client = HostClient::new(&env, &host);
+        let guardian = Address::generate(&env);
+
+        client.grant_role(&admin, &GUARDIAN, &guardian);
+        assert!(client.has_role(&GUARDIAN, &guardian));
+        assert_eq!(client.admin(), admin);
+    }
+}
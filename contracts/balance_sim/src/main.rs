@@ -0,0 +1,109 @@
+// Monte Carlo balancing for the raid engine. Runs citadel_types::resolve_raid,
+// the exact code the contracts execute, over a sweep of attacker troop counts,
+// troop levels and defender wall HP. Each cell samples defenders with a random
+// garrison and resource balance and reports win rates, the destruction
+// distribution and expected loot per troop spent.
+//
+//     cargo run -p balance_sim -- --troops 10,50,100 --levels 1,2,3 --walls 100,500 --format csv
+
+use citadel_types::{resolve_raid, Base};
+use soroban_sdk::{testutils::Address as _, Address, Env};
+use std::{env, fs, process};
+
+mod rng;
+mod sweep;
+
+use sweep::{run, Config};
+
+const USAGE: &str = "Usage: balance_sim [options]
+
+  --troops <list>       Attacker troop counts            [default: 10,25,50,100]
+  --levels <list>       Troop levels, both sides         [default: 1,2,3,4,5]
+  --walls <list>        Defender wall HP                 [default: 100,200,500,1000]
+  --garrison <max>      Defender garrison, 0..=max       [default: 100]
+  --resources <max>     Defender resources, 0..=max      [default: 10000]
+  --samples <n>         Defenders sampled per cell       [default: 1000]
+  --seed <n>            RNG seed                         [default: 1]
+  --format <csv|json>                                    [default: csv]
+  --out <path>          Write to a file instead of stdout";
+
+fn fail(message: &str) -> ! {
+    eprintln!("{}\n\n{}", message, USAGE);
+    process::exit(2);
+}
+
+fn parse<T: std::str::FromStr>(flag: &str, value: &str) -> T {
+    value.parse().unwrap_or_else(|_| fail(&format!("Invalid value for {}: {}", flag, value)))
+}
+
+fn parse_list<T: std::str::FromStr>(flag: &str, value: &str) -> Vec<T> {
+    value.split(',').map(|v| parse(flag, v.trim())).collect()
+}
+
+fn parse_args() -> (Config, String, Option<String>) {
+    let mut config = Config::default();
+    let mut format = String::from("csv");
+    let mut out = None;
+
+    let mut args = env::args().skip(1);
+    while let Some(flag) = args.next() {
+        if flag == "--help" || flag == "-h" {
+            println!("{}", USAGE);
+            process::exit(0);
+        }
+        let value = args.next().unwrap_or_else(|| fail(&format!("Missing value for {}", flag)));
+        match flag.as_str() {
+            "--troops" => config.troops = parse_list(&flag, &value),
+            "--levels" => config.levels = parse_list(&flag, &value),
+            "--walls" => config.walls = parse_list(&flag, &value),
+            "--garrison" => config.max_garrison = parse(&flag, &value),
+            "--resources" => config.max_resources = parse(&flag, &value),
+            "--samples" => config.samples = parse(&flag, &value),
+            "--seed" => config.seed = parse(&flag, &value),
+            "--format" => format = value,
+            "--out" => out = Some(value),
+            _ => fail(&format!("Unknown option {}", flag)),
+        }
+    }
+    if format != "csv" && format != "json" {
+        fail(&format!("Unknown format {}", format));
+    }
+    if config.samples == 0 {
+        fail("--samples must be positive");
+    }
+    (config, format, out)
+}
+
+fn main() {
+    let (config, format, out) = parse_args();
+
+    // One host for the whole run, bases only need it for their owner address
+    let env = Env::default();
+    let attacker = Address::generate(&env);
+    let defender = Address::generate(&env);
+    let base = |owner: &Address, troop_level, troop_count, wall_hp, resources| Base {
+        owner: owner.clone(),
+        level: 1,
+        principal: 0,
+        resources,
+        wall_hp,
+        max_wall_hp: wall_hp,
+        troop_level,
+        troop_count,
+        max_troops: u32::MAX,
+        last_claim_time: 0,
+        shield_end_time: 0,
+    };
+
+    let rows = run(&config, |cell, garrison, resources| {
+        let att = base(&attacker, cell.level, cell.troops, 0, 0);
+        let def = base(&defender, cell.level, garrison, cell.wall_hp, resources);
+        resolve_raid(&env, &att, &def, cell.troops)
+    });
+
+    let output = if format == "json" { sweep::to_json(&rows) } else { sweep::to_csv(&rows) };
+    match out {
+        Some(path) => fs::write(&path, output).unwrap_or_else(|e| fail(&format!("Cannot write {}: {}", path, e))),
+        None => print!("{}", output),
+    }
+}
@@ -0,0 +1,191 @@
+use crate::rng::Rng;
+use citadel_types::RaidOutcome;
+use serde_json::{json, Value};
+
+pub struct Config {
+    pub troops: Vec<u32>,
+    pub levels: Vec<u32>,
+    pub walls: Vec<u32>,
+    pub max_garrison: u32,
+    pub max_resources: i128,
+    pub samples: u32,
+    pub seed: u64,
+}
+
+impl Default for Config {
+    fn default() -> Self {
+        Config {
+            troops: vec![10, 25, 50, 100],
+            levels: vec![1, 2, 3, 4, 5],
+            walls: vec![100, 200, 500, 1000],
+            max_garrison: 100,
+            max_resources: 10_000,
+            samples: 1000,
+            seed: 1,
+        }
+    }
+}
+
+// One point of the sweep
+#[derive(Clone, Copy)]
+pub struct Cell {
+    pub troops: u32,
+    pub level: u32,
+    pub wall_hp: u32,
+}
+
+pub struct Row {
+    pub cell: Cell,
+    pub samples: u32,
+    pub breach_rate: f64,        // Got through the wall
+    pub win_rate: f64,           // Also beat the garrison (50%+ destruction)
+    pub destruction: [f64; 4],   // Share of raids at 0, 30, 50 and 100%
+    pub expected_loot: f64,
+    pub expected_troops_lost: f64,
+    pub loot_per_troop: Option<f64>, // None when no troops were ever lost
+}
+
+const DESTRUCTION_LEVELS: [u32; 4] = [0, 30, 50, 100];
+
+// Runs `raid(cell, garrison, resources)` `samples` times per cell
+pub fn run(config: &Config, mut raid: impl FnMut(Cell, u32, i128) -> RaidOutcome) -> Vec<Row> {
+    let mut rng = Rng::new(config.seed);
+    let mut rows = Vec::new();
+
+    for &level in &config.levels {
+        for &wall_hp in &config.walls {
+            for &troops in &config.troops {
+                let cell = Cell { troops, level, wall_hp };
+                let (mut breaches, mut wins) = (0u32, 0u32);
+                let mut destruction = [0u32; 4];
+                let (mut loot, mut lost) = (0f64, 0f64);
+
+                for _ in 0..config.samples {
+                    let garrison = rng.up_to(config.max_garrison as u64) as u32;
+                    let resources = rng.up_to(config.max_resources.max(0) as u64) as i128;
+                    let outcome = raid(cell, garrison, resources);
+
+                    breaches += outcome.battle.success as u32;
+                    wins += (outcome.battle.destruction_percent >= 50) as u32;
+                    if let Some(i) = DESTRUCTION_LEVELS.iter().position(|&d| d == outcome.battle.destruction_percent) {
+                        destruction[i] += 1;
+                    }
+                    loot += outcome.loot as f64;
+                    lost += outcome.troops_lost as f64;
+                }
+
+                let n = config.samples as f64;
+                rows.push(Row {
+                    cell,
+                    samples: config.samples,
+                    breach_rate: breaches as f64 / n,
+                    win_rate: wins as f64 / n,
+                    destruction: destruction.map(|count| count as f64 / n),
+                    expected_loot: loot / n,
+                    expected_troops_lost: lost / n,
+                    loot_per_troop: if lost > 0.0 { Some(loot / lost) } else { None },
+                });
+            }
+        }
+    }
+    rows
+}
+
+pub fn to_csv(rows: &[Row]) -> String {
+    let mut out = String::from(
+        "troops,level,wall_hp,samples,breach_rate,win_rate,destruction_0,destruction_30,destruction_50,destruction_100,expected_loot,expected_troops_lost,loot_per_troop\n",
+    );
+    for row in rows {
+        let loot_per_troop = row.loot_per_troop.map(|v| format!("{:.4}", v)).unwrap_or_default();
+        out.push_str(&format!(
+            "{},{},{},{},{:.4},{:.4},{:.4},{:.4},{:.4},{:.4},{:.4},{:.4},{}\n",
+            row.cell.troops,
+            row.cell.level,
+            row.cell.wall_hp,
+            row.samples,
+            row.breach_rate,
+            row.win_rate,
+            row.destruction[0],
+            row.destruction[1],
+            row.destruction[2],
+            row.destruction[3],
+            row.expected_loot,
+            row.expected_troops_lost,
+            loot_per_troop,
+        ));
+    }
+    out
+}
+
+pub fn to_json(rows: &[Row]) -> String {
+    let rows: Vec<Value> = rows
+        .iter()
+        .map(|row| {
+            json!({
+                "troops": row.cell.troops,
+                "level": row.cell.level,
+                "wall_hp": row.cell.wall_hp,
+                "samples": row.samples,
+                "breach_rate": row.breach_rate,
+                "win_rate": row.win_rate,
+                "destruction": DESTRUCTION_LEVELS
+                    .iter()
+                    .zip(row.destruction)
+                    .map(|(percent, share)| json!({ "percent": percent, "share": share }))
+                    .collect::<Vec<_>>(),
+                "expected_loot": row.expected_loot,
+                "expected_troops_lost": row.expected_troops_lost,
+                "loot_per_troop": row.loot_per_troop,
+            })
+        })
+        .collect();
+    let mut out = serde_json::to_string_pretty(&rows).expect("rows serialize");
+    out.push('\n');
+    out
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use citadel_types::{BattlePhase, BattleResult};
+
+    fn config() -> Config {
+        Config { troops: vec![10, 20], levels: vec![1], walls: vec![100, 200, 300], samples: 50, ..Config::default() }
+    }
+
+    // Fixed outcome so the aggregation itself can be checked
+    fn ambush(_: Cell, _: u32, resources: i128) -> RaidOutcome {
+        RaidOutcome {
+            battle: BattleResult { success: true, destruction_percent: 30, phase: BattlePhase::Ambush, remaining_stamina: 0 },
+            loot: resources / 10,
+            fee: 0,
+            troops_lost: 5,
+            wall_damage: 0,
+            shields_defender: true,
+        }
+    }
+
+    #[test]
+    fn one_row_per_cell() {
+        let rows = run(&config(), ambush);
+        assert_eq!(rows.len(), 6);
+        assert_eq!(to_csv(&rows).lines().count(), 7);
+    }
+
+    #[test]
+    fn rates_and_distribution_add_up() {
+        let rows = run(&config(), ambush);
+        for row in &rows {
+            assert_eq!(row.breach_rate, 1.0);
+            assert_eq!(row.win_rate, 0.0);
+            assert_eq!(row.destruction, [0.0, 1.0, 0.0, 0.0]);
+            assert_eq!(row.expected_troops_lost, 5.0);
+            assert!((row.loot_per_troop.unwrap() - row.expected_loot / 5.0).abs() < 1e-9);
+        }
+    }
+
+    #[test]
+    fn same_seed_same_table() {
+        assert_eq!(to_json(&run(&config(), ambush)), to_json(&run(&config(), ambush)));
+    }
+}
@@ -1,26 +1,73 @@
-#![no_std]
-use soroban_sdk::{contracttype, Address, Env};
-
-#[contracttype]
-#[derive(Clone, Debug)]
-pub struct BaseState {
-    pub level: u32,
-    pub wall_hp: u32,
-    pub army_hp: u32,
-    pub townhall_hp: u32,
+use soroban_sdk::{token, Address, Env};
+use citadel_types::Base;
+use crate::DataKey;
+
+pub const MAX_LEVEL: u32 = 5;
+const BASE_UPGRADE_COST: i128 = 100;
+
+// Defences per level: (wall HP, garrison size, garrison troop level)
+fn level_stats(level: u32) -> (u32, u32, u32) {
+    match level {
+        1 => (100, 10, 1),
+        2 => (200, 15, 1),
+        3 => (350, 20, 2),
+        4 => (550, 25, 2),
+        5 => (800, 30, 3),
+        _ => panic!("Unknown base level"),
+    }
 }
 
-pub fn get_base_stats(env: &Env, user: Address) -> BaseState {
-    // In a real contract, fetch from storage. Returning default for interface.
-    BaseState {
-        level: 1,
-        wall_hp: 1000,
-        army_hp: 500,
-        townhall_hp: 2000,
+// A base at `level` with full walls and garrison. Wagers are escrowed as
+// tokens, so the canonical principal and resources stay at zero here.
+fn base_at(owner: Address, level: u32) -> Base {
+    let (wall_hp, troops, troop_level) = level_stats(level);
+    Base {
+        owner,
+        level,
+        principal: 0,
+        resources: 0,
+        wall_hp,
+        max_wall_hp: wall_hp,
+        troop_level,
+        troop_count: troops,
+        max_troops: troops,
+        last_claim_time: 0,
+        shield_end_time: 0,
     }
 }
 
+// Cost to go from `level` to `level + 1`, quadratic in the current level
+pub fn upgrade_cost(level: u32) -> i128 {
+    BASE_UPGRADE_COST * (level as i128) * (level as i128)
+}
+
+pub fn get_base(env: &Env, user: Address) -> Base {
+    env.storage()
+        .persistent()
+        .get(&DataKey::Base(user.clone()))
+        .unwrap_or_else(|| base_at(user, 1))
+}
+
 pub fn upgrade_base(env: &Env, user: Address, resource_spent: i128) {
     user.require_auth();
-    // Logic to consume resource and boost HP stats
+
+    let base = get_base(env, user.clone());
+    if base.level >= MAX_LEVEL {
+        panic!("Base at max level");
+    }
+
+    // `resource_spent` is the most the player is willing to pay
+    let cost = upgrade_cost(base.level);
+    if resource_spent < cost {
+        panic!("Insufficient resources for upgrade");
+    }
+
+    // Consume resource into the treasury
+    let token_id: Address = env.storage().instance().get(&DataKey::Token).expect("Not initialized");
+    let treasury: Address = env.storage().instance().get(&DataKey::FeeRecipient).expect("Not initialized");
+    token::Client::new(env, &token_id).transfer(&user, &treasury, &cost);
+
+    // Raise walls and garrison to the next level
+    let upgraded = base_at(user.clone(), base.level + 1);
+    env.storage().persistent().set(&DataKey::Base(user), &upgraded);
 }
@@ -1,14 +1,16 @@
-#![no_std]
-use soroban_sdk::{Env, Address};
-use crate::base_management::BaseState;
+use soroban_sdk::{Address, Env};
+use citadel_types::{get_troop_stats, simulate_raid, BattleResult};
+use crate::base_management::get_base;
 
-pub fn resolve_raid(env: &Env, attacker: Address, defender: Address, troops: u32) -> u32 {
-    // 1. Fetch Defender Base
-    // 2. Phase 1: Wall
-    // 3. Phase 2: Army
-    // 4. Phase 3: Townhall
-    
-    // Simulating simplified return: Destruction Percentage (0-100)
-    let destruction = 50; 
-    destruction
+// Plays the raid out with the shared battle engine, neither base is changed
+pub fn resolve_raid(env: &Env, attacker: Address, defender: Address, troops: u32) -> BattleResult {
+    let base_attacker = get_base(env, attacker);
+    let base_defender = get_base(env, defender);
+    if troops > base_attacker.troop_count {
+        panic!("Not enough troops");
+    }
+
+    let attacker_stats = get_troop_stats(base_attacker.troop_level);
+    let defender_stats = get_troop_stats(base_defender.troop_level);
+    simulate_raid(&base_defender, troops, &attacker_stats, &defender_stats)
 }
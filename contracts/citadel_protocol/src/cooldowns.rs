@@ -0,0 +1,19 @@
+use soroban_sdk::{Address, Env};
+use citadel_types::{check_cooldown, enforce_cooldown, Action};
+use crate::DataKey;
+
+fn yield_engine(env: &Env) -> Option<Address> {
+    env.storage().instance().get(&DataKey::YieldEngine)
+}
+
+pub fn check(env: &Env, user: &Address, action: Action) {
+    if let Some(engine) = yield_engine(env) {
+        check_cooldown(env, &engine, user, action);
+    }
+}
+
+pub fn enforce(env: &Env, user: &Address, action: Action) {
+    if let Some(engine) = yield_engine(env) {
+        enforce_cooldown(env, &engine, user, action);
+    }
+}
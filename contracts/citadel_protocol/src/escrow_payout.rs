@@ -1,25 +1,109 @@
-#![no_std]
-use soroban_sdk::{Env, Address, token};
+use soroban_sdk::{contractevent, contracttype, token, Address, Env};
+use citadel_types::split_wager;
+use fixed_math::{mul_div, Rounding};
+use crate::DataKey;
 
-pub fn raid_escrow(env: &Env, attacker: Address, amount: i128) {
+#[contractevent(data_format = "single-value")]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct AttackerPayout {
+    #[topic]
+    pub attacker: Address,
+    pub amount: i128,
+}
+
+#[contractevent(data_format = "single-value")]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct DefenderPayout {
+    #[topic]
+    pub defender: Address,
+    pub amount: i128,
+}
+
+#[contractevent(data_format = "single-value")]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct FeePayout {
+    #[topic]
+    pub recipient: Address,
+    pub amount: i128,
+}
+
+// An attacker's locked stake, only the named defender's raid can settle it
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct Wager {
+    pub defender: Address,
+    pub amount: i128,
+}
+
+pub const DEFAULT_FEE_BPS: u32 = 200; // 2%
+pub const MAX_FEE_BPS: u32 = 1000;    // 10%
+
+pub fn fee_bps(env: &Env) -> u32 {
+    env.storage().instance().get(&DataKey::FeeBps).unwrap_or(DEFAULT_FEE_BPS)
+}
+
+pub fn validate_fee(fee_bps: u32) {
+    if fee_bps > MAX_FEE_BPS {
+        panic!("Fee too high");
+    }
+}
+
+fn token_client(env: &Env) -> token::Client<'_> {
+    let token_id: Address = env.storage().instance().get(&DataKey::Token).expect("Not initialized");
+    token::Client::new(env, &token_id)
+}
+
+pub fn raid_escrow(env: &Env, attacker: Address, defender: Address, amount: i128) {
     attacker.require_auth();
+    if amount <= 0 {
+        panic!("Wager must be positive");
+    }
+    if attacker == defender {
+        panic!("Cannot raid own base");
+    }
+
+    let key = DataKey::Escrow(attacker.clone());
+    if env.storage().persistent().has(&key) {
+        panic!("Raid already in progress");
+    }
+
     // Lock funds in contract
+    token_client(env).transfer(&attacker, env.current_contract_address(), &amount);
+    env.storage().persistent().set(&key, &Wager { defender, amount });
+}
+
+// Releases the attacker's escrow so it can be paid out exactly once, and only
+// against the defender it was placed on
+pub fn take_escrow(env: &Env, attacker: Address, defender: &Address) -> i128 {
+    let key = DataKey::Escrow(attacker);
+    let wager: Wager = env.storage().persistent().get(&key).expect("No wager escrowed");
+    if wager.defender != *defender {
+        panic!("Wager is on another defender");
+    }
+    env.storage().persistent().remove(&key);
+    wager.amount
 }
 
 pub fn distribute_reward(env: &Env, defender: Address, attacker: Address, destruction_pct: u32, wager: i128) {
-    let fee = wager * 2 / 100; // 2% Fee
+    let fee = mul_div(wager, fee_bps(env) as i128, 10000, Rounding::Ceil); // Rounded towards the protocol
     let pool = wager - fee;
-    
-    if destruction_pct < 30 {
-        // Defender wins everything
-        // token::Client::new(env, &token_id).transfer(&env.current_contract_address(), &defender, &pool);
-    } else if destruction_pct < 100 {
-         // Proportional
-         let attacker_share = (pool * destruction_pct as i128) / 100;
-         let defender_share = pool - attacker_share;
-         // Transfer logic
-    } else {
-        // Attacker wins 2x (Total Pool)
-        // Transfer all to attacker
+
+    let (attacker_share, defender_share) = split_wager(env, pool, destruction_pct);
+
+    let client = token_client(env);
+    let contract = env.current_contract_address();
+
+    if attacker_share > 0 {
+        client.transfer(&contract, &attacker, &attacker_share);
+        AttackerPayout { attacker, amount: attacker_share }.publish(env);
+    }
+    if defender_share > 0 {
+        client.transfer(&contract, &defender, &defender_share);
+        DefenderPayout { defender, amount: defender_share }.publish(env);
+    }
+    if fee > 0 {
+        let recipient: Address = env.storage().instance().get(&DataKey::FeeRecipient).expect("Not initialized");
+        client.transfer(&contract, &recipient, &fee);
+        FeePayout { recipient, amount: fee }.publish(env);
     }
 }
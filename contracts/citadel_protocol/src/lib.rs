@@ -1,24 +1,134 @@
 #![no_std]
-use soroban_sdk::{contract, contractimpl, Env, Address};
+use soroban_sdk::{contract, contractimpl, contracttype, symbol_short, BytesN, Env, Address, Symbol};
+use citadel_types::{Action, Base};
 
 mod base_management;
 mod combat_engine;
+mod cooldowns;
 mod escrow_payout;
+mod test;
+
+#[contracttype]
+#[derive(Clone)]
+pub enum DataKey {
+    Token,              // Escrowed wager token
+    FeeRecipient,       // Receives the platform fee leg
+    FeeBps,             // Platform fee on each wager, DEFAULT_FEE_BPS until changed
+    YieldEngine,        // Cooldown authority, raids are gated on it once set
+    Escrow(Address),    // Attacker -> Wager
+    Base(Address),      // User -> Base
+}
+
+const VERSION: u32 = 1;
+
+// Pausable features
+const RAID: Symbol = symbol_short!("raid");
+const UPGRADE: Symbol = symbol_short!("upgrade");
+
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub enum ProtocolChange {
+    FeeBps(u32),
+    FeeRecipient(Address),
+    YieldEngine(Address),
+    TimelockDelay(u64),
+}
+
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct QueuedChange {
+    pub id: u64,
+    pub eta: u64,
+    pub change: ProtocolChange,
+}
 
 #[contract]
 pub struct CitadelProtocol;
 
+access_control::role_entrypoints!(CitadelProtocol);
+pausable::pause_entrypoints!(CitadelProtocol);
+timelock::timelock_entrypoints!(CitadelProtocol, QueuedChange);
+
 #[contractimpl]
 impl CitadelProtocol {
-    pub fn init(env: Env) {
-        // Init logic
+    pub fn __constructor(env: Env, admin: Address, token: Address, fee_recipient: Address) {
+        access_control::init(&env, &admin);
+        env.storage().instance().set(&DataKey::Token, &token);
+        env.storage().instance().set(&DataKey::FeeRecipient, &fee_recipient);
+    }
+
+    // Admin: queue a fee, engine or delay change
+    pub fn propose_change(env: Env, change: ProtocolChange) -> u64 {
+        if let ProtocolChange::FeeBps(fee_bps) = &change {
+            escrow_payout::validate_fee(*fee_bps);
+        }
+        timelock::queue(&env, change)
+    }
+
+    pub fn execute_change(env: Env, id: u64) {
+        match timelock::execute(&env, id) {
+            ProtocolChange::FeeBps(fee_bps) => {
+                escrow_payout::validate_fee(fee_bps);
+                env.storage().instance().set(&DataKey::FeeBps, &fee_bps);
+            }
+            ProtocolChange::FeeRecipient(recipient) => env.storage().instance().set(&DataKey::FeeRecipient, &recipient),
+            // Route raid cooldowns through YieldEngine, which must grant this contract the GAME role
+            ProtocolChange::YieldEngine(engine) => env.storage().instance().set(&DataKey::YieldEngine, &engine),
+            ProtocolChange::TimelockDelay(seconds) => timelock::set_delay(&env, seconds),
+        }
+    }
+
+    pub fn fee_bps(env: Env) -> u32 {
+        escrow_payout::fee_bps(&env)
     }
-    
+
+    // Admin: swap the contract code in place, escrows and bases are kept.
+    // `upgrade` is the player-facing base upgrade.
+    pub fn upgrade_contract(env: Env, new_wasm_hash: BytesN<32>) {
+        access_control::require_admin(&env);
+        env.deployer().update_current_contract_wasm(new_wasm_hash);
+    }
+
+    pub fn version(_env: Env) -> u32 {
+        VERSION
+    }
+
     pub fn upgrade(env: Env, user: Address, resources: i128) {
+        pausable::when_not_paused(&env, &UPGRADE);
         base_management::upgrade_base(&env, user, resources);
     }
-    
-    pub fn start_raid(env: Env, attacker: Address, wager: i128) {
-        escrow_payout::raid_escrow(&env, attacker, wager);
+
+    pub fn get_base(env: Env, user: Address) -> Base {
+        base_management::get_base(&env, user)
+    }
+
+    pub fn upgrade_cost(env: Env, user: Address) -> i128 {
+        let base = base_management::get_base(&env, user);
+        if base.level >= base_management::MAX_LEVEL {
+            return 0;
+        }
+        base_management::upgrade_cost(base.level)
+    }
+
+    // Lock `wager` against `defender`, the raid can only be resolved on that base
+    pub fn start_raid(env: Env, attacker: Address, defender: Address, wager: i128) {
+        pausable::when_not_paused(&env, &RAID);
+        // Fail before locking funds if the army is still resting
+        cooldowns::check(&env, &attacker, Action::Raid);
+        escrow_payout::raid_escrow(&env, attacker, defender, wager);
+    }
+
+    // Fight the raid out with the shared battle engine and pay out the escrowed
+    // wager, returns the destruction percentage
+    pub fn resolve_raid(env: Env, attacker: Address, defender: Address, troops: u32) -> u32 {
+        pausable::when_not_paused(&env, &RAID);
+        attacker.require_auth();
+
+        cooldowns::enforce(&env, &attacker, Action::Raid);
+        let wager = escrow_payout::take_escrow(&env, attacker.clone(), &defender);
+        let battle = combat_engine::resolve_raid(&env, attacker.clone(), defender.clone(), troops);
+        escrow_payout::distribute_reward(&env, defender, attacker, battle.destruction_percent, wager);
+
+        battle.destruction_percent
     }
 }
@@ -0,0 +1,225 @@
+#![cfg(test)]
+extern crate std;
+
+use crate::{CitadelProtocol, CitadelProtocolClient};
+use soroban_sdk::{testutils::{Address as _, Events}, token, vec, Address, BytesN, Env, IntoVal, Symbol, Val, Vec};
+
+struct Setup<'a> {
+    env: Env,
+    protocol: CitadelProtocolClient<'a>,
+    token: token::Client<'a>,
+    token_admin: token::StellarAssetClient<'a>,
+    treasury: Address,
+}
+
+fn setup<'a>() -> Setup<'a> {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let issuer = Address::generate(&env);
+    let sac = env.register_stellar_asset_contract_v2(issuer);
+    let token = token::Client::new(&env, &sac.address());
+    let token_admin = token::StellarAssetClient::new(&env, &sac.address());
+
+    let treasury = Address::generate(&env);
+    let protocol = CitadelProtocolClient::new(&env, &env.register(CitadelProtocol, (Address::generate(&env), sac.address(), treasury.clone())));
+
+    Setup { env, protocol, token, token_admin, treasury }
+}
+
+// A player funded with `balance` whose base has been upgraded to `level`
+fn player(s: &Setup, level: u32, balance: i128) -> Address {
+    let user = Address::generate(&s.env);
+    s.token_admin.mint(&user, &balance);
+    for _ in 1..level {
+        let cost = s.protocol.upgrade_cost(&user);
+        s.token_admin.mint(&user, &cost);
+        s.protocol.upgrade(&user, &cost);
+    }
+    user
+}
+
+// Events the protocol itself published during the last call
+fn protocol_events(s: &Setup) -> Vec<(Address, Vec<Val>, Val)> {
+    let mut events = Vec::new(&s.env);
+    for event in s.env.events().all().iter() {
+        if event.0 == s.protocol.address {
+            events.push_back(event);
+        }
+    }
+    events
+}
+
+fn payout_event(s: &Setup, name: &str, to: &Address, amount: i128) -> (Address, Vec<Val>, Val) {
+    let topics = vec![&s.env, Symbol::new(&s.env, name).into_val(&s.env), to.into_val(&s.env)];
+    (s.protocol.address.clone(), topics, amount.into_val(&s.env))
+}
+
+#[test]
+fn start_raid_escrows_the_wager() {
+    let s = setup();
+    let attacker = player(&s, 1, 1_000);
+    let defender = player(&s, 1, 0);
+
+    s.protocol.start_raid(&attacker, &defender, &400);
+    assert_eq!(s.token.balance(&attacker), 600);
+    assert_eq!(s.token.balance(&s.protocol.address), 400);
+}
+
+#[test]
+#[should_panic(expected = "Raid already in progress")]
+fn one_escrow_per_attacker() {
+    let s = setup();
+    let attacker = player(&s, 1, 1_000);
+    let defender = player(&s, 1, 0);
+
+    s.protocol.start_raid(&attacker, &defender, &400);
+    s.protocol.start_raid(&attacker, &defender, &400);
+}
+
+#[test]
+fn resolve_raid_pays_every_leg() {
+    let s = setup();
+    let attacker = player(&s, 1, 1_000);
+    let defender = player(&s, 1, 0);
+    s.protocol.start_raid(&attacker, &defender, &1_000);
+
+    // 2% fee rounded up, then the attacker takes 30% of the rest
+    assert_eq!(s.protocol.resolve_raid(&attacker, &defender, &10), 30);
+    assert_eq!(
+        protocol_events(&s),
+        vec![
+            &s.env,
+            payout_event(&s, "attacker_payout", &attacker, 294),
+            payout_event(&s, "defender_payout", &defender, 686),
+            payout_event(&s, "fee_payout", &s.treasury, 20),
+        ]
+    );
+    assert_eq!(s.token.balance(&attacker), 294);
+    assert_eq!(s.token.balance(&defender), 686);
+    assert_eq!(s.token.balance(&s.treasury), 20);
+    assert_eq!(s.token.balance(&s.protocol.address), 0);
+}
+
+#[test]
+#[should_panic(expected = "No wager escrowed")]
+fn raid_resolves_once() {
+    let s = setup();
+    let attacker = player(&s, 1, 1_000);
+    let defender = player(&s, 1, 0);
+    s.protocol.start_raid(&attacker, &defender, &1_000);
+
+    s.protocol.resolve_raid(&attacker, &defender, &10);
+    s.protocol.resolve_raid(&attacker, &defender, &10);
+}
+
+#[test]
+#[should_panic(expected = "Wager is on another defender")]
+fn wager_only_settles_against_its_defender() {
+    let s = setup();
+    let attacker = player(&s, 1, 1_000);
+    let strong = player(&s, 5, 0);
+    let weak = player(&s, 1, 0);
+    s.protocol.start_raid(&attacker, &strong, &1_000);
+
+    s.protocol.resolve_raid(&attacker, &weak, &10);
+}
+
+#[test]
+#[should_panic(expected = "Cannot raid own base")]
+fn cannot_raid_own_base() {
+    let s = setup();
+    let attacker = player(&s, 1, 1_000);
+
+    s.protocol.start_raid(&attacker, &attacker, &1_000);
+}
+
+#[test]
+fn raids_are_fought_with_the_shared_engine() {
+    let s = setup();
+    let veteran = player(&s, 3, 1_000);
+    let rookie = player(&s, 1, 1_000);
+    let defender = player(&s, 1, 0);
+
+    // Level 2 troops overrun a level 1 garrison, level 1 troops are held off
+    s.protocol.start_raid(&veteran, &defender, &100);
+    assert_eq!(s.protocol.resolve_raid(&veteran, &defender, &20), 100);
+    s.protocol.start_raid(&rookie, &defender, &100);
+    assert_eq!(s.protocol.resolve_raid(&rookie, &defender, &10), 30);
+}
+
+#[test]
+#[should_panic(expected = "Not enough troops")]
+fn raids_are_capped_at_the_garrison() {
+    let s = setup();
+    let attacker = player(&s, 1, 1_000);
+    let defender = player(&s, 1, 0);
+
+    s.protocol.start_raid(&attacker, &defender, &100);
+    s.protocol.resolve_raid(&attacker, &defender, &11);
+}
+
+#[test]
+fn upgrade_cost_is_quadratic_and_goes_to_the_treasury() {
+    let s = setup();
+    let user = player(&s, 1, 5_000);
+
+    let mut paid = 0;
+    for level in 1..5 {
+        let cost = 100 * (level as i128) * (level as i128);
+        assert_eq!(s.protocol.upgrade_cost(&user), cost);
+        // Offering more than the cost only pays the cost
+        s.protocol.upgrade(&user, &(cost + 50));
+        paid += cost;
+        assert_eq!(s.token.balance(&s.treasury), paid);
+    }
+    assert_eq!(s.token.balance(&user), 5_000 - 3_000);
+}
+
+#[test]
+fn upgrades_follow_the_level_table() {
+    let s = setup();
+    let user = player(&s, 1, 0);
+    let base = s.protocol.get_base(&user);
+    assert_eq!((base.level, base.wall_hp, base.troop_count, base.troop_level), (1, 100, 10, 1));
+
+    let user = player(&s, 2, 0);
+    let base = s.protocol.get_base(&user);
+    assert_eq!((base.level, base.wall_hp, base.max_wall_hp, base.troop_count, base.max_troops), (2, 200, 200, 15, 15));
+
+    let user = player(&s, 5, 0);
+    let base = s.protocol.get_base(&user);
+    assert_eq!((base.level, base.wall_hp, base.troop_count, base.troop_level), (5, 800, 30, 3));
+}
+
+#[test]
+#[should_panic(expected = "Insufficient resources for upgrade")]
+fn upgrade_needs_the_full_cost() {
+    let s = setup();
+    let user = player(&s, 1, 1_000);
+    s.protocol.upgrade(&user, &99);
+}
+
+#[test]
+#[should_panic(expected = "Base at max level")]
+fn max_level_caps_upgrades() {
+    let s = setup();
+    let user = player(&s, 5, 10_000);
+    s.protocol.upgrade(&user, &10_000);
+}
+
+#[test]
+fn nothing_to_pay_at_max_level() {
+    let s = setup();
+    let user = player(&s, 5, 0);
+    assert_eq!(s.protocol.upgrade_cost(&user), 0);
+}
+
+#[test]
+fn only_the_admin_upgrades_the_contract() {
+    let s = setup();
+    assert_eq!(s.protocol.version(), 1);
+
+    s.env.set_auths(&[]);
+    assert!(s.protocol.try_upgrade_contract(&BytesN::from_array(&s.env, &[7; 32])).is_err());
+}
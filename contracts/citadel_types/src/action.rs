@@ -0,0 +1,11 @@
+use soroban_sdk::contracttype;
+
+// Actions YieldEngine keeps a cooldown for
+#[contracttype]
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum Action {
+    Raid,
+    Train,
+    Upgrade,
+    Scout,
+}
@@ -0,0 +1,37 @@
+use soroban_sdk::{contracttype, Address};
+
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct Base {
+    pub owner: Address,
+    pub level: u32,
+    pub principal: i128, // Deposited XLM, never raidable
+    pub resources: i128, // Accrued yield and loot, the only raidable balance
+    pub wall_hp: u32,
+    pub max_wall_hp: u32,
+    pub troop_level: u32, // Defender troop level
+    pub troop_count: u32, // Defender troop count (garrison)
+    pub max_troops: u32,
+    pub last_claim_time: u64,
+    pub shield_end_time: u64,
+}
+
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct TroopStats {
+    pub damage: u32,
+    pub stamina: u32,
+}
+
+// Stats saturate at u32::MAX instead of wrapping for absurd levels
+pub fn get_troop_stats(level: u32) -> TroopStats {
+    match level {
+        1 => TroopStats { damage: 5, stamina: 20 },
+        2 => TroopStats { damage: 10, stamina: 30 },
+        3 => TroopStats { damage: 15, stamina: 45 },
+        _ => TroopStats {
+            damage: level.saturating_mul(5).saturating_add(5),
+            stamina: level.saturating_mul(10).saturating_add(20),
+        },
+    }
+}
@@ -1,7 +1,7 @@
-#![no_std]
-use crate::state_storage::{Base, TroopStats};
-use soroban_sdk::{Env};
+use crate::base::{Base, TroopStats};
+use soroban_sdk::contracttype;
 
+#[contracttype]
 #[derive(Clone, Copy, PartialEq, Eq, Debug)]
 pub enum BattlePhase {
     Breach,
@@ -9,6 +9,8 @@ pub enum BattlePhase {
     End,
 }
 
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
 pub struct BattleResult {
     pub success: bool,
     pub destruction_percent: u32,
@@ -16,18 +18,29 @@ pub struct BattleResult {
     pub remaining_stamina: u32,
 }
 
+// Products of up to three u32s, so every intermediate is done in u128 and can't overflow
 pub fn simulate_raid(
-    _env: &Env, 
-    base: &Base, 
-    attacker_troops: u32, 
+    base: &Base,
+    attacker_troops: u32,
     attacker_stats: &TroopStats,
     defender_stats: &TroopStats
 ) -> BattleResult {
-    
+
     // Phase 1: Breach
-    let total_potential = (attacker_troops as u64) * (attacker_stats.stamina as u64) * (attacker_stats.damage as u64);
-    
-    if total_potential < (base.wall_hp as u64) {
+    // No troops never breach, even a wall that is already down
+    if attacker_troops == 0 {
+        return BattleResult {
+            success: false,
+            destruction_percent: 0,
+            phase: BattlePhase::Breach,
+            remaining_stamina: 0,
+        };
+    }
+
+    let total_stamina_pool = (attacker_troops as u128) * (attacker_stats.stamina as u128);
+    let total_potential = total_stamina_pool * (attacker_stats.damage as u128);
+
+    if total_potential < (base.wall_hp as u128) {
         // Failed Breach
         return BattleResult {
             success: false,
@@ -36,16 +49,14 @@ pub fn simulate_raid(
             remaining_stamina: 0,
         };
     }
-    
+
     // Stamina consumed = (Wall HP / DMG)
     // Avoid divide by zero
     let dmg = if attacker_stats.damage == 0 { 1 } else { attacker_stats.damage };
-    let stamina_consumed = (base.wall_hp + dmg - 1) / dmg;
-    
-    let total_stamina_pool = attacker_troops * attacker_stats.stamina;
-    
+    let stamina_consumed = base.wall_hp.div_ceil(dmg) as u128;
+
     // This check is redundant if total_potential check passes, but good for safety
-    if (stamina_consumed as u64) > (total_stamina_pool as u64) {
+    if stamina_consumed > total_stamina_pool {
          return BattleResult {
             success: false,
             destruction_percent: 0,
@@ -53,33 +64,34 @@ pub fn simulate_raid(
             remaining_stamina: 0,
         };
     }
-    
+
     let remaining_pool = total_stamina_pool - stamina_consumed;
-    
+
     // Phase 2: Ambush
-    let defender_power = (base.troop_count as u64) * (defender_stats.damage as u64) * (defender_stats.stamina as u64);
-    
+    let defender_power = (base.troop_count as u128) * (defender_stats.damage as u128) * (defender_stats.stamina as u128);
+
     // Attacker remaining damage potential
-    let attacker_remaining_damage = (remaining_pool as u64) * (attacker_stats.damage as u64);
-    
+    let attacker_remaining_damage = remaining_pool * (attacker_stats.damage as u128);
+
     if attacker_remaining_damage > defender_power {
         // Attacker wins Ambush (50%+)
         // Assuming Wipeout (100%) if significant overkill, else just Victory (50%)
         // Let's say if remaining damage > defender power * 1.5 => 100%
         let destruction = if attacker_remaining_damage > (defender_power * 3 / 2) { 100 } else { 50 };
-        return BattleResult {
+        let remaining_stamina = (attacker_remaining_damage - defender_power) / (dmg as u128);
+        BattleResult {
             success: true,
             destruction_percent: destruction,
             phase: BattlePhase::End,
-            remaining_stamina: ((attacker_remaining_damage - defender_power) / (dmg as u64)) as u32,
-        };
+            remaining_stamina: u32::try_from(remaining_stamina).unwrap_or(u32::MAX),
+        }
     } else {
         // Defender wins Ambush
-        return BattleResult {
-            success: true, 
+        BattleResult {
+            success: true,
             destruction_percent: 30, // Breached but stopped
             phase: BattlePhase::Ambush,
             remaining_stamina: 0,
-        };
+        }
     }
 }
@@ -0,0 +1,29 @@
+use soroban_sdk::{contractclient, Address, Env};
+use crate::Action;
+
+// The slice of YieldEngine that game contracts call into
+#[allow(dead_code)] // Only used through the generated client
+#[contractclient(name = "YieldEngineClient")]
+pub trait YieldEngineInterface {
+    fn check_cooldown(env: Env, user: Address, action: Action);
+    fn remaining_cooldown(env: Env, user: Address, action: Action) -> u64;
+    fn trigger_cooldown(env: Env, game: Address, user: Address, action: Action);
+}
+
+// Seconds until `user` may take `action` again, 0 when it is ready
+pub fn remaining_cooldown(env: &Env, engine: &Address, user: &Address, action: Action) -> u64 {
+    YieldEngineClient::new(env, engine).remaining_cooldown(user, &action)
+}
+
+// Panics if `user` is still cooling down from `action`
+pub fn check_cooldown(env: &Env, engine: &Address, user: &Address, action: Action) {
+    YieldEngineClient::new(env, engine).check_cooldown(user, &action);
+}
+
+// Panics if `user` is still cooling down from `action`, then starts a new
+// cooldown with the calling contract as the game
+pub fn enforce_cooldown(env: &Env, engine: &Address, user: &Address, action: Action) {
+    let client = YieldEngineClient::new(env, engine);
+    client.check_cooldown(user, &action);
+    client.trigger_cooldown(&env.current_contract_address(), user, &action);
+}
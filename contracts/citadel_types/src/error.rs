@@ -0,0 +1,15 @@
+use soroban_sdk::{contracterror, panic_with_error, Env};
+
+// Raised instead of wrapping or aborting when economy math leaves its range
+#[contracterror]
+#[derive(Copy, Clone, Debug, Eq, PartialEq, PartialOrd, Ord)]
+#[repr(u32)]
+pub enum EngineError {
+    Overflow = 1,
+    Underflow = 2,
+}
+
+// Unwraps a checked_* result, failing the call with `error` if it went out of range
+pub fn checked<T>(env: &Env, value: Option<T>, error: EngineError) -> T {
+    value.unwrap_or_else(|| panic_with_error!(env, error))
+}
@@ -0,0 +1,26 @@
+#![no_std]
+// Canonical game types and the engine math every contract shares: bases and
+// troops, the raid simulation and its outcome, loot and payout splits, and
+// cooldown actions along with the YieldEngine calls that enforce them.
+// Contracts keep their own storage keys, everything else comes from here so
+// the rules can't drift between crates.
+
+mod action;
+mod base;
+mod battle;
+mod cooldown;
+mod error;
+mod loot;
+mod payout;
+mod raid;
+
+pub use action::Action;
+pub use base::{get_troop_stats, Base, TroopStats};
+pub use battle::{simulate_raid, BattlePhase, BattleResult};
+pub use cooldown::{check_cooldown, enforce_cooldown, remaining_cooldown};
+pub use error::{checked, EngineError};
+pub use loot::{loot_bps, loot_pool, BASE_LOOT_BPS, MAX_LOOT_BPS};
+pub use payout::{calculate_payout, split_wager};
+pub use raid::{resolve_raid, RaidOutcome, SHIELD_THRESHOLD};
+
+mod test;
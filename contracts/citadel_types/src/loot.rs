@@ -0,0 +1,62 @@
+// Log-scaled loot curve, the on-chain port of `calculateLoot` in api/battleLogic.js:
+//     multiplier = log10(1 + diff / 10) + 1
+// A raid puts BASE_LOOT_BPS of the defender's resources at stake when both sides
+// are even, growing with the log of the power gap so stomps have diminishing returns.
+
+use crate::error::{checked, EngineError};
+use fixed_math::{checked_mul_div, log10, mul_div, Rounding, WAD};
+use soroban_sdk::Env;
+
+pub const BASE_LOOT_BPS: i128 = 1000; // 10%
+pub const MAX_LOOT_BPS: i128 = 4000;  // 40%
+
+// Any gap past this is already at the cap, clamping keeps the WAD maths in range
+const MAX_POWER_GAP: i128 = 1_000_000;
+
+// Share of the defender's resources at stake, in bps
+pub fn loot_bps(attacker_power: i128, defender_power: i128) -> i128 {
+    let diff = attacker_power.saturating_sub(defender_power).clamp(0, MAX_POWER_GAP);
+    let multiplier = WAD + log10(WAD + mul_div(diff, WAD, 10, Rounding::Floor));
+
+    // Round to the nearest bp so exact powers of ten land on round numbers
+    let bps = (BASE_LOOT_BPS * multiplier + WAD / 2) / WAD;
+    if bps > MAX_LOOT_BPS { MAX_LOOT_BPS } else { bps }
+}
+
+// Rounded down, so the defender never loses more than the curve says
+pub fn loot_pool(env: &Env, resources: i128, attacker_power: i128, defender_power: i128) -> i128 {
+    let bps = loot_bps(attacker_power, defender_power);
+    checked(env, checked_mul_div(resources, bps, 10000, Rounding::Floor), EngineError::Overflow)
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn even_fight_stakes_base_loot() {
+        assert_eq!(loot_bps(500, 500), BASE_LOOT_BPS);
+        assert_eq!(loot_bps(100, 500), BASE_LOOT_BPS);
+    }
+
+    #[test]
+    fn loot_grows_with_log_of_gap() {
+        assert_eq!(loot_bps(10, 0), 1301);
+        assert_eq!(loot_bps(90, 0), 2000);
+        assert_eq!(loot_bps(990, 0), 3000);
+        assert_eq!(loot_bps(1000, 100), 2959);
+    }
+
+    #[test]
+    fn huge_gaps_are_capped() {
+        assert_eq!(loot_bps(9_990, 0), MAX_LOOT_BPS);
+        assert_eq!(loot_bps(1_000_000_000, 0), MAX_LOOT_BPS);
+    }
+
+    #[test]
+    fn pool_is_share_of_resources() {
+        let env = Env::default();
+        assert_eq!(loot_pool(&env, 10_000, 1000, 100), 2959);
+        assert_eq!(loot_pool(&env, 0, 1000, 100), 0);
+    }
+}
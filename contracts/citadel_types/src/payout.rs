@@ -0,0 +1,41 @@
+use crate::error::{checked, EngineError};
+use fixed_math::{checked_mul_div, Rounding};
+use soroban_sdk::Env;
+
+// Raider share rounds down and the platform fee rounds up, so dust stays with the protocol
+fn split(env: &Env, loot_pool: i128, payout_pct: i128, fee_pct: i128) -> (i128, i128, i128) {
+    let payout = checked(env, checked_mul_div(loot_pool, payout_pct, 100, Rounding::Floor), EngineError::Overflow);
+    let fee = checked(env, checked_mul_div(loot_pool, fee_pct, 100, Rounding::Ceil), EngineError::Overflow);
+    (payout, fee, loot_pool - payout - fee)
+}
+
+// YieldRaiders loot tiers, returns (Raider, Platform, Defender)
+pub fn calculate_payout(env: &Env, damage_percent: u32, loot_pool: i128) -> (i128, i128, i128) {
+    if damage_percent < 30 {
+        (0, 0, loot_pool)
+    } else if damage_percent < 50 {
+        // Breach: 30% destruction -> 20% pool
+        split(env, loot_pool, 20, 2)
+    } else if damage_percent < 100 {
+        // Victory: 50% destruction -> 50% pool
+        split(env, loot_pool, 50, 5)
+    } else {
+        // Wipeout: 100% destruction -> 90% pool
+        split(env, loot_pool, 90, 10)
+    }
+}
+
+// CitadelProtocol wager split after the fee, returns (Attacker, Defender)
+pub fn split_wager(env: &Env, pool: i128, destruction_pct: u32) -> (i128, i128) {
+    if destruction_pct < 30 {
+        // Defender wins everything
+        (0, pool)
+    } else if destruction_pct < 100 {
+        // Proportional
+        let attacker_share = checked(env, checked_mul_div(pool, destruction_pct as i128, 100, Rounding::Floor), EngineError::Overflow);
+        (attacker_share, pool - attacker_share)
+    } else {
+        // Attacker wins 2x (Total Pool)
+        (pool, 0)
+    }
+}
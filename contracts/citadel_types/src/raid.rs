@@ -0,0 +1,121 @@
+use soroban_sdk::{contracttype, Env};
+use crate::base::{get_troop_stats, Base};
+use crate::battle::{simulate_raid, BattleResult};
+use crate::loot::loot_pool;
+use crate::payout::calculate_payout;
+
+// Destruction at or above this breaches the base and shields the defender
+pub const SHIELD_THRESHOLD: u32 = 30;
+
+// Everything a raid changes, worked out without touching either base
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct RaidOutcome {
+    pub battle: BattleResult,
+    pub loot: i128,          // Resources moved from defender to attacker
+    pub fee: i128,           // Resources the defender loses to the platform
+    pub troops_lost: u32,
+    pub wall_damage: u32,
+    pub shields_defender: bool,
+}
+
+// Both bases must have had their resources accrued up to now
+pub fn resolve_raid(env: &Env, attacker: &Base, defender: &Base, troop_count: u32) -> RaidOutcome {
+    let att_stats = get_troop_stats(attacker.troop_level);
+    let def_stats = get_troop_stats(defender.troop_level);
+
+    let battle = simulate_raid(defender, troop_count, &att_stats, &def_stats);
+
+    let (loot, fee, wall_damage) = if battle.success {
+        // Principal is protected, only resources are at stake
+        let attacker_power = (troop_count as i128) * (att_stats.stamina as i128) * (att_stats.damage as i128);
+        let defender_power = (defender.wall_hp as i128)
+            + (defender.troop_count as i128) * (def_stats.stamina as i128) * (def_stats.damage as i128);
+        let pool = loot_pool(env, defender.resources, attacker_power, defender_power);
+        let (raider_share, fee, _) = calculate_payout(env, battle.destruction_percent, pool);
+
+        // A successful raid always gets through the wall
+        (raider_share, fee, defender.wall_hp)
+    } else {
+        let total_potential = (troop_count as u128) * (att_stats.stamina as u128) * (att_stats.damage as u128);
+        let damage = if total_potential < defender.wall_hp as u128 { total_potential as u32 } else { defender.wall_hp };
+        (0, 0, damage)
+    };
+
+    let troops_lost = if battle.destruction_percent == 100 {
+        troop_count / 10
+    } else if battle.destruction_percent >= SHIELD_THRESHOLD {
+        troop_count / 2
+    } else {
+        troop_count
+    };
+
+    RaidOutcome {
+        shields_defender: battle.destruction_percent >= SHIELD_THRESHOLD,
+        battle,
+        loot,
+        fee,
+        troops_lost,
+        wall_damage,
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::battle::BattlePhase;
+    use soroban_sdk::{testutils::Address as _, Address, Env};
+
+    fn base(env: &Env, troop_count: u32, wall_hp: u32, resources: i128) -> Base {
+        Base {
+            owner: Address::generate(env),
+            level: 1,
+            principal: 0,
+            resources,
+            wall_hp,
+            max_wall_hp: wall_hp,
+            troop_level: 1,
+            troop_count,
+            max_troops: 100,
+            last_claim_time: 0,
+            shield_end_time: 0,
+        }
+    }
+
+    #[test]
+    fn stopped_in_ambush_loses_half_the_troops() {
+        let env = Env::default();
+        let outcome = resolve_raid(&env, &base(&env, 10, 100, 1000), &base(&env, 10, 100, 1000), 10);
+
+        assert_eq!(outcome.battle.phase, BattlePhase::Ambush);
+        assert_eq!(outcome.battle.destruction_percent, 30);
+        // Even fight stakes 10% of 1000, the breach tier pays 20% of that
+        assert_eq!((outcome.loot, outcome.fee), (20, 2));
+        assert_eq!(outcome.troops_lost, 5);
+        assert_eq!(outcome.wall_damage, 100);
+        assert!(outcome.shields_defender);
+    }
+
+    #[test]
+    fn failed_breach_only_dents_the_wall() {
+        let env = Env::default();
+        let outcome = resolve_raid(&env, &base(&env, 10, 0, 0), &base(&env, 0, 500, 1000), 2);
+
+        assert!(!outcome.battle.success);
+        assert_eq!((outcome.loot, outcome.fee), (0, 0));
+        assert_eq!(outcome.wall_damage, 200);
+        assert_eq!(outcome.troops_lost, 2);
+        assert!(!outcome.shields_defender);
+    }
+
+    #[test]
+    fn zero_troops_never_succeed() {
+        let env = Env::default();
+        let outcome = resolve_raid(&env, &base(&env, 10, 0, 0), &base(&env, 0, 0, 1000), 0);
+
+        assert!(!outcome.battle.success);
+        assert_eq!(outcome.battle.phase, BattlePhase::Breach);
+        assert_eq!((outcome.loot, outcome.fee), (0, 0));
+        assert_eq!(outcome.troops_lost, 0);
+    }
+}
@@ -0,0 +1,99 @@
+#![cfg(test)]
+extern crate std;
+
+// Property tests: the engine must stay in range for any troop count, level,
+// wall and balance a base could end up with.
+
+use crate::{calculate_payout, get_troop_stats, loot_bps, resolve_raid, simulate_raid, Base, BASE_LOOT_BPS, MAX_LOOT_BPS};
+use proptest::prelude::*;
+use soroban_sdk::{testutils::Address as _, Address, Env};
+
+fn base(env: &Env, troop_level: u32, troop_count: u32, wall_hp: u32, resources: i128) -> Base {
+    Base {
+        owner: Address::generate(env),
+        level: 1,
+        principal: 0,
+        resources,
+        wall_hp,
+        max_wall_hp: wall_hp,
+        troop_level,
+        troop_count,
+        max_troops: u32::MAX,
+        last_claim_time: 0,
+        shield_end_time: 0,
+    }
+}
+
+// Full u32 range, with the edges picked far more often than a uniform draw would
+fn any_u32() -> impl Strategy<Value = u32> {
+    prop_oneof![Just(0), Just(1), Just(u32::MAX), 0..1_000u32, any::<u32>()]
+}
+
+proptest! {
+    #[test]
+    fn simulation_stays_in_range(
+        troops in any_u32(),
+        att_level in any_u32(),
+        def_level in any_u32(),
+        def_troops in any_u32(),
+        wall_hp in any_u32(),
+    ) {
+        let env = Env::default();
+        let defender = base(&env, def_level, def_troops, wall_hp, 0);
+        let result = simulate_raid(&defender, troops, &get_troop_stats(att_level), &get_troop_stats(def_level));
+
+        prop_assert!([0, 30, 50, 100].contains(&result.destruction_percent));
+        prop_assert_eq!(result.success, result.destruction_percent > 0);
+    }
+
+    #[test]
+    fn more_troops_never_do_less(
+        troops in 0..u32::MAX,
+        extra in any_u32(),
+        level in any_u32(),
+        def_troops in any_u32(),
+        wall_hp in any_u32(),
+    ) {
+        let env = Env::default();
+        let defender = base(&env, level, def_troops, wall_hp, 0);
+        let stats = get_troop_stats(level);
+        let fewer = simulate_raid(&defender, troops, &stats, &stats);
+        let more = simulate_raid(&defender, troops.saturating_add(extra), &stats, &stats);
+
+        prop_assert!(more.destruction_percent >= fewer.destruction_percent);
+    }
+
+    #[test]
+    fn raid_never_takes_more_than_exists(
+        troops in any_u32(),
+        att_level in any_u32(),
+        def_level in any_u32(),
+        def_troops in any_u32(),
+        wall_hp in any_u32(),
+        resources in prop_oneof![Just(0i128), Just(i128::MAX), 0..i128::MAX],
+    ) {
+        let env = Env::default();
+        let attacker = base(&env, att_level, troops, 0, 0);
+        let defender = base(&env, def_level, def_troops, wall_hp, resources);
+        let outcome = resolve_raid(&env, &attacker, &defender, troops);
+
+        prop_assert!(outcome.loot >= 0 && outcome.fee >= 0);
+        prop_assert!(outcome.loot + outcome.fee <= resources);
+        prop_assert!(outcome.troops_lost <= troops);
+        prop_assert!(outcome.wall_damage <= wall_hp);
+    }
+
+    #[test]
+    fn loot_share_is_bounded(attacker_power in any::<i128>(), defender_power in any::<i128>()) {
+        let bps = loot_bps(attacker_power, defender_power);
+        prop_assert!((BASE_LOOT_BPS..=MAX_LOOT_BPS).contains(&bps));
+    }
+
+    #[test]
+    fn payout_splits_the_whole_pool(damage in 0..=100u32, pool in 0..i128::MAX) {
+        let env = Env::default();
+        let (raider, fee, defender) = calculate_payout(&env, damage, pool);
+        prop_assert!(raider >= 0 && fee >= 0 && defender >= 0);
+        prop_assert_eq!(raider + fee + defender, pool);
+    }
+}
@@ -0,0 +1,78 @@
+use soroban_sdk::{contracttype, Env, Vec};
+use crate::DataKey;
+
+pub const DEFAULT_APY_BPS: u32 = 1000; // 10% APY
+const MAX_APY_BPS: u32 = 10000;
+const MAX_BOOST_BPS: u32 = 50000; // 5x
+
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct ApyChange {
+    pub timestamp: u64,
+    pub apy_bps: u32,
+}
+
+// Deposits of at least `min_deposit` earn the base APY scaled by `boost_bps` (10000 = 1x)
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct ApyTier {
+    pub min_deposit: i128,
+    pub boost_bps: u32,
+}
+
+pub fn current_apy(env: &Env) -> u32 {
+    env.storage().instance().get(&DataKey::Apy).unwrap_or(DEFAULT_APY_BPS)
+}
+
+pub fn history(env: &Env) -> Vec<ApyChange> {
+    env.storage().persistent().get(&DataKey::ApyHistory).unwrap_or(Vec::new(env))
+}
+
+pub fn validate_apy(apy_bps: u32) {
+    if apy_bps > MAX_APY_BPS {
+        panic!("APY too high");
+    }
+}
+
+// Caller must have moved the reward index up to now first
+pub fn set_apy(env: &Env, apy_bps: u32) {
+    validate_apy(apy_bps);
+    env.storage().instance().set(&DataKey::Apy, &apy_bps);
+
+    let mut changes = history(env);
+    changes.push_back(ApyChange { timestamp: env.ledger().timestamp(), apy_bps });
+    env.storage().persistent().set(&DataKey::ApyHistory, &changes);
+}
+
+pub fn tiers(env: &Env) -> Vec<ApyTier> {
+    env.storage().instance().get(&DataKey::ApyTiers).unwrap_or(Vec::new(env))
+}
+
+pub fn validate_tiers(tiers: &Vec<ApyTier>) {
+    let mut last_min: i128 = -1;
+    for tier in tiers.iter() {
+        if tier.min_deposit <= last_min {
+            panic!("Tiers must be sorted by min_deposit");
+        }
+        if tier.boost_bps < 10000 || tier.boost_bps > MAX_BOOST_BPS {
+            panic!("Invalid tier boost");
+        }
+        last_min = tier.min_deposit;
+    }
+}
+
+pub fn set_tiers(env: &Env, tiers: Vec<ApyTier>) {
+    validate_tiers(&tiers);
+    env.storage().instance().set(&DataKey::ApyTiers, &tiers);
+}
+
+// Boost of the highest tier the deposit qualifies for
+pub fn boost_for(env: &Env, deposit: i128) -> u32 {
+    let mut boost = 10000;
+    for tier in tiers(env).iter() {
+        if deposit >= tier.min_deposit {
+            boost = tier.boost_bps;
+        }
+    }
+    boost
+}
@@ -1,82 +1,450 @@
 #![no_std]
-use soroban_sdk::{contract, contractimpl, contracttype, Address, Env, Symbol};
+use soroban_sdk::{contract, contractimpl, contracttype, symbol_short, token, Address, BytesN, Env, Symbol, Vec};
+use access_control::OPERATOR;
+use fixed_math::{mul_div, Rounding};
+use rewards::{earned_since, pending_index, refresh_weight, settle, update_index};
+
+mod apy;
+mod lockup;
+mod rewards;
+mod yield_source;
+mod test;
+
+pub use apy::{ApyChange, ApyTier};
+pub use lockup::LockPosition;
+pub use yield_source::{YieldSource, YieldSourceClient};
 
 #[contracttype]
 #[derive(Clone)]
 pub enum DataKey {
-    Principal(Address),  // User -> Amount
-    Yield(Address),      // User -> Amount
-    LastTs(Address),     // User -> Timestamp
+    Token,               // Deposit token
+    Shares(Address),     // User -> Liquid vault shares
+    Lock(Address),       // User -> LockPosition, shares not in `Shares`
+    Principal(Address),  // User -> Deposited amount (cost basis)
+    Yield(Address),      // User -> Settled, unclaimed yield
+    UserIndex(Address),  // User -> RewardIndex at last settlement
+    Weight(Address),     // User -> Deposit scaled by their APY tier
+    TotalShares,
+    IdleAssets,          // Principal held by the vault itself, not deployed
     TotalPrincipal,
-    TotalYield,
+    TotalYield,          // Yield accrued to users but not yet claimed
+    RewardReserve,       // Tokens set aside for future yield, not yet accrued
+    TotalWeight,
+    RewardIndex,         // Cumulative yield per unit of weight, scaled by INDEX_SCALE
+    LastAccrual,         // Timestamp of last index update
+    Apy,                 // Base APY in bps
+    ApyHistory,          // Vec<ApyChange>
+    ApyTiers,            // Vec<ApyTier>, sorted by min_deposit
+    YieldSource,         // Lending adapter principal is deployed to
+}
+
+const VERSION: u32 = 1;
+
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub enum VaultChange {
+    Apy(u32),
+    ApyTiers(Vec<ApyTier>),
+    YieldSource(Option<Address>),
+    TimelockDelay(u64),
+    GrantRole(Symbol, Address),  // For OPERATOR, which can move any user's yield
+}
+
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct QueuedChange {
+    pub id: u64,
+    pub eta: u64,
+    pub change: VaultChange,
 }
 
 #[contract]
 pub struct CitadelVault;
 
+access_control::role_entrypoints!(CitadelVault);
+pausable::pause_entrypoints!(CitadelVault);
+timelock::timelock_entrypoints!(CitadelVault, QueuedChange);
+
+fn token_client(env: &Env) -> token::Client<'_> {
+    let token_id: Address = env.storage().instance().get(&DataKey::Token).expect("Vault not initialized");
+    token::Client::new(env, &token_id)
+}
+
+// Pausable features
+const DEPOSIT: Symbol = symbol_short!("deposit");
+const WITHDRAW: Symbol = symbol_short!("withdraw");
+const YIELD: Symbol = symbol_short!("yield");       // Accruing and claiming
+const TRANSFER: Symbol = symbol_short!("transfer"); // Game contracts moving yield
+
+pub(crate) fn get_instance(env: &Env, key: &DataKey) -> i128 {
+    env.storage().instance().get(key).unwrap_or(0)
+}
+
+pub(crate) fn get_persistent(env: &Env, key: &DataKey) -> i128 {
+    env.storage().persistent().get(key).unwrap_or(0)
+}
+
+pub(crate) fn shares_to_assets(env: &Env, shares: i128) -> i128 {
+    to_assets(shares, get_instance(env, &DataKey::TotalShares), yield_source::total_assets(env))
+}
+
+// Value of the user's liquid shares
+pub(crate) fn assets_of(env: &Env, user: &Address) -> i128 {
+    shares_to_assets(env, get_persistent(env, &DataKey::Shares(user.clone())))
+}
+
+// Shares minted for `assets`, rounded down in favour of the vault
+fn to_shares(assets: i128, total_shares: i128, total_assets: i128) -> i128 {
+    if total_shares == 0 || total_assets == 0 { return assets; }
+    mul_div(assets, total_shares, total_assets, Rounding::Floor)
+}
+
+// Shares burned to pay out `assets`, rounded up in favour of the vault
+fn to_shares_up(assets: i128, total_shares: i128, total_assets: i128) -> i128 {
+    if total_shares == 0 || total_assets == 0 { return assets; }
+    mul_div(assets, total_shares, total_assets, Rounding::Ceil)
+}
+
+fn to_assets(shares: i128, total_shares: i128, total_assets: i128) -> i128 {
+    if total_shares == 0 { return shares; }
+    mul_div(shares, total_assets, total_shares, Rounding::Floor)
+}
+
+// Takes `amount` from the user and mints shares for it at the current price,
+// the caller decides whether they land liquid or locked
+fn mint(env: &Env, user: &Address, amount: i128) -> i128 {
+    let total_shares = get_instance(env, &DataKey::TotalShares);
+    let shares = to_shares(amount, total_shares, yield_source::total_assets(env));
+    if shares == 0 { panic!("Deposit too small"); }
+
+    token_client(env).transfer(user, env.current_contract_address(), &amount);
+
+    let principal_key = DataKey::Principal(user.clone());
+    env.storage().persistent().set(&principal_key, &(get_persistent(env, &principal_key) + amount));
+
+    // Update Global
+    env.storage().instance().set(&DataKey::TotalShares, &(total_shares + shares));
+    env.storage().instance().set(&DataKey::IdleAssets, &(get_instance(env, &DataKey::IdleAssets) + amount));
+    env.storage().instance().set(&DataKey::TotalPrincipal, &(get_instance(env, &DataKey::TotalPrincipal) + amount));
+    yield_source::push_idle(env);
+
+    shares
+}
+
+// Burns `shares` already removed from the user's balance and releases `assets` from the vault
+fn burn(env: &Env, shares: i128, assets: i128) {
+    env.storage().instance().set(&DataKey::TotalShares, &(get_instance(env, &DataKey::TotalShares) - shares));
+    yield_source::ensure_idle(env, assets);
+    env.storage().instance().set(&DataKey::IdleAssets, &(get_instance(env, &DataKey::IdleAssets) - assets));
+}
+
+// Burns liquid `shares` for `assets` and sends them to the user
+fn burn_and_pay(env: &Env, user: &Address, shares: i128, assets: i128) {
+    let shares_key = DataKey::Shares(user.clone());
+    let user_shares = get_persistent(env, &shares_key);
+    if shares > user_shares {
+        panic!("Insufficient shares");
+    }
+    env.storage().persistent().set(&shares_key, &(user_shares - shares));
+    burn(env, shares, assets);
+
+    token_client(env).transfer(&env.current_contract_address(), user, &assets);
+}
+
+// Lowers the user's cost basis when principal leaves the vault
+fn reduce_principal(env: &Env, user: &Address, amount: i128) {
+    let key = DataKey::Principal(user.clone());
+    let principal = get_persistent(env, &key);
+    let reduced = if amount > principal { principal } else { amount };
+    env.storage().persistent().set(&key, &(principal - reduced));
+    env.storage().instance().set(&DataKey::TotalPrincipal, &(get_instance(env, &DataKey::TotalPrincipal) - reduced));
+}
+
 #[contractimpl]
 impl CitadelVault {
-    // Deposit Principal
-    pub fn deposit(env: Env, user: Address, amount: i128) {
+    pub fn __constructor(env: Env, admin: Address, token: Address) {
+        access_control::init(&env, &admin);
+        env.storage().instance().set(&DataKey::Token, &token);
+        apy::set_apy(&env, apy::DEFAULT_APY_BPS);
+    }
+
+    // Admin: swap the contract code in place, balances and locks are kept
+    pub fn upgrade(env: Env, new_wasm_hash: BytesN<32>) {
+        access_control::require_admin(&env);
+        env.deployer().update_current_contract_wasm(new_wasm_hash);
+    }
+
+    pub fn version(_env: Env) -> u32 {
+        VERSION
+    }
+
+    // Admin: queue a parameter or role change
+    pub fn propose_change(env: Env, change: VaultChange) -> u64 {
+        match &change {
+            VaultChange::Apy(apy_bps) => apy::validate_apy(*apy_bps),
+            VaultChange::ApyTiers(tiers) => apy::validate_tiers(tiers),
+            _ => {}
+        }
+        timelock::queue(&env, change)
+    }
+
+    pub fn execute_change(env: Env, id: u64) {
+        match timelock::execute(&env, id) {
+            // The APY applies from now on
+            VaultChange::Apy(apy_bps) => {
+                update_index(&env);
+                apy::set_apy(&env, apy_bps);
+            }
+            // Existing users pick new tiers up on their next touch
+            VaultChange::ApyTiers(tiers) => apy::set_tiers(&env, tiers),
+            // Route principal to a lending adapter, or keep it idle with `None`
+            VaultChange::YieldSource(source) => {
+                yield_source::recall_all(&env);
+                match source {
+                    Some(source) => env.storage().instance().set(&DataKey::YieldSource, &source),
+                    None => env.storage().instance().remove(&DataKey::YieldSource),
+                }
+                yield_source::push_idle(&env);
+            }
+            VaultChange::TimelockDelay(seconds) => timelock::set_delay(&env, seconds),
+            VaultChange::GrantRole(role, account) => access_control::grant_queued_role(&env, &role, &account),
+        }
+    }
+
+    // Operator: move accrued yield from one user to another, principal is never touched.
+    // Returns the amount actually moved, capped at what `from` has accrued.
+    pub fn transfer_yield(env: Env, operator: Address, from: Address, to: Address, amount: i128) -> i128 {
+        pausable::when_not_paused(&env, &TRANSFER);
+        access_control::require_role(&env, &OPERATOR, &operator);
+        if amount <= 0 { panic!("Amount must be positive"); }
+
+        settle(&env, &from);
+        settle(&env, &to);
+
+        let from_key = DataKey::Yield(from);
+        let available = get_persistent(&env, &from_key);
+        let moved = if amount > available { available } else { amount };
+        if moved == 0 { return 0; }
+
+        let to_key = DataKey::Yield(to);
+        env.storage().persistent().set(&from_key, &(available - moved));
+        env.storage().persistent().set(&to_key, &(get_persistent(&env, &to_key) + moved));
+
+        moved
+    }
+
+    // Top up the reward reserve, open to admins and fee flows alike
+    pub fn fund_reserve(env: Env, from: Address, amount: i128) {
+        from.require_auth();
+        if amount <= 0 { panic!("Amount must be positive"); }
+
+        // Accrue at the old funding level so the top-up only pays for the future
+        update_index(&env);
+        token_client(&env).transfer(&from, env.current_contract_address(), &amount);
+        env.storage().instance().set(&DataKey::RewardReserve, &(get_instance(&env, &DataKey::RewardReserve) + amount));
+    }
+
+    // Deposit Principal, returns the shares minted
+    pub fn deposit(env: Env, user: Address, amount: i128) -> i128 {
+        pausable::when_not_paused(&env, &DEPOSIT);
+        user.require_auth();
+        if amount <= 0 { panic!("Amount must be positive"); }
+
+        // Settle yield on the old share balance before it changes
+        settle(&env, &user);
+        let shares = mint(&env, &user, amount);
+        let shares_key = DataKey::Shares(user.clone());
+        env.storage().persistent().set(&shares_key, &(get_persistent(&env, &shares_key) + shares));
+        refresh_weight(&env, &user);
+
+        shares
+    }
+
+    // Deposit Principal locked for 7, 30 or 90 days at a boosted APY
+    pub fn deposit_locked(env: Env, user: Address, amount: i128, days: u32) -> LockPosition {
+        pausable::when_not_paused(&env, &DEPOSIT);
         user.require_auth();
-        
-        // Update Principal
-        let key = DataKey::Principal(user.clone());
-        let current: i128 = env.storage().persistent().get(&key).unwrap_or(0);
-        env.storage().persistent().set(&key, &(current + amount));
-        
-        // Update Start Time for Yield if new
-        let ts_key = DataKey::LastTs(user.clone());
-        if !env.storage().persistent().has(&ts_key) {
-             env.storage().persistent().set(&ts_key, &env.ledger().timestamp());
+        if amount <= 0 { panic!("Amount must be positive"); }
+
+        settle(&env, &user);
+        let shares = mint(&env, &user, amount);
+        let lock = lockup::open_lock(&env, &user, shares, days);
+        refresh_weight(&env, &user);
+
+        lock
+    }
+
+    // Move a matured lock back into liquid shares
+    pub fn unlock(env: Env, user: Address) -> i128 {
+        pausable::when_not_paused(&env, &WITHDRAW);
+        user.require_auth();
+        let lock = lockup::get_lock(&env, &user).expect("No active lock");
+        if env.ledger().timestamp() < lock.unlock_ts {
+            panic!("Lock not matured");
         }
-        
-        // Update Global
-        let total_key = DataKey::TotalPrincipal;
-        let total: i128 = env.storage().instance().get(&total_key).unwrap_or(0);
-        env.storage().instance().set(&total_key, &(total + amount));
+
+        settle(&env, &user);
+        lockup::close_lock(&env, &user);
+        let shares_key = DataKey::Shares(user.clone());
+        env.storage().persistent().set(&shares_key, &(get_persistent(&env, &shares_key) + lock.shares));
+        refresh_weight(&env, &user);
+
+        lock.shares
     }
 
-    // Accrue Yield (Simulated APY logic)
+    // Exit a lock before it matures, the penalty is added to the reward reserve.
+    // Returns the assets paid out to the user.
+    pub fn break_lock(env: Env, user: Address) -> i128 {
+        pausable::when_not_paused(&env, &WITHDRAW);
+        user.require_auth();
+
+        settle(&env, &user);
+        let lock = lockup::close_lock(&env, &user);
+        if env.ledger().timestamp() >= lock.unlock_ts {
+            panic!("Lock matured, use unlock");
+        }
+
+        let assets = shares_to_assets(&env, lock.shares);
+        let penalty = mul_div(assets, lockup::EARLY_EXIT_PENALTY_BPS, 10000, Rounding::Ceil);
+        burn(&env, lock.shares, assets);
+        reduce_principal(&env, &user, assets);
+        env.storage().instance().set(&DataKey::RewardReserve, &(get_instance(&env, &DataKey::RewardReserve) + penalty));
+        refresh_weight(&env, &user);
+
+        token_client(&env).transfer(&env.current_contract_address(), &user, &(assets - penalty));
+        assets - penalty
+    }
+
+    // Withdraw an exact amount of assets, returns the shares burned
+    pub fn withdraw(env: Env, user: Address, amount: i128) -> i128 {
+        pausable::when_not_paused(&env, &WITHDRAW);
+        user.require_auth();
+        if amount <= 0 { panic!("Amount must be positive"); }
+
+        settle(&env, &user);
+        let shares = to_shares_up(amount, get_instance(&env, &DataKey::TotalShares), yield_source::total_assets(&env));
+        burn_and_pay(&env, &user, shares, amount);
+        reduce_principal(&env, &user, amount);
+        refresh_weight(&env, &user);
+
+        shares
+    }
+
+    // Redeem an exact amount of shares, returns the assets paid out
+    pub fn redeem(env: Env, user: Address, shares: i128) -> i128 {
+        pausable::when_not_paused(&env, &WITHDRAW);
+        user.require_auth();
+        if shares <= 0 { panic!("Amount must be positive"); }
+
+        settle(&env, &user);
+        let assets = to_assets(shares, get_instance(&env, &DataKey::TotalShares), yield_source::total_assets(&env));
+        burn_and_pay(&env, &user, shares, assets);
+        reduce_principal(&env, &user, assets);
+        refresh_weight(&env, &user);
+
+        assets
+    }
+
+    // Accrue Yield (Simulated APY logic), returns the yield credited to the user
     pub fn accrue_yield(env: Env, user: Address) -> i128 {
-        let principal_key = DataKey::Principal(user.clone());
-        let principal: i128 = env.storage().persistent().get(&principal_key).unwrap_or(0);
-        
-        if principal == 0 { return 0; }
-
-        let ts_key = DataKey::LastTs(user.clone());
-        let last_ts: u64 = env.storage().persistent().get(&ts_key).unwrap_or(env.ledger().timestamp());
-        let now = env.ledger().timestamp();
-        
-        if now <= last_ts { return 0; }
-        
-        let elapsed = (now - last_ts) as i128;
-        let apy_bps = 1000; // 10% APY
-        let seconds_in_year = 31536000;
-        
-        // Yield = Principal * APY * (Elapsed / Year)
-        // Using large multiplier for precision
-        let yield_amount = (principal * apy_bps * elapsed) / (10000 * seconds_in_year);
-        
-        // Update Storage
+        pausable::when_not_paused(&env, &YIELD);
+        settle(&env, &user)
+    }
+
+    // Pay out all settled yield, returns the amount claimed
+    pub fn claim_yield(env: Env, user: Address) -> i128 {
+        pausable::when_not_paused(&env, &YIELD);
+        user.require_auth();
+        settle(&env, &user);
+
         let yield_key = DataKey::Yield(user.clone());
-        let current_yield: i128 = env.storage().persistent().get(&yield_key).unwrap_or(0);
-        env.storage().persistent().set(&yield_key, &(current_yield + yield_amount));
-        
-        // Update Timestamp
-        env.storage().persistent().set(&ts_key, &now);
-        
-        yield_amount
-    }
-    
-    // Getters
+        let amount = get_persistent(&env, &yield_key);
+        if amount == 0 { return 0; }
+
+        env.storage().persistent().set(&yield_key, &0i128);
+        env.storage().instance().set(&DataKey::TotalYield, &(get_instance(&env, &DataKey::TotalYield) - amount));
+
+        token_client(&env).transfer(&env.current_contract_address(), &user, &amount);
+        amount
+    }
+
+    // Views
+    pub fn preview_deposit(env: Env, amount: i128) -> i128 {
+        to_shares(amount, get_instance(&env, &DataKey::TotalShares), yield_source::total_assets(&env))
+    }
+
+    pub fn preview_redeem(env: Env, shares: i128) -> i128 {
+        to_assets(shares, get_instance(&env, &DataKey::TotalShares), yield_source::total_assets(&env))
+    }
+
+    pub fn total_assets(env: Env) -> i128 {
+        yield_source::total_assets(&env)
+    }
+
+    pub fn yield_source(env: Env) -> Option<Address> {
+        yield_source::get_source(&env)
+    }
+
+    pub fn total_shares(env: Env) -> i128 {
+        get_instance(&env, &DataKey::TotalShares)
+    }
+
+    pub fn get_shares(env: Env, user: Address) -> i128 {
+        get_persistent(&env, &DataKey::Shares(user))
+    }
+
+    pub fn get_lock(env: Env, user: Address) -> Option<LockPosition> {
+        lockup::get_lock(&env, &user)
+    }
+
     pub fn get_balance(env: Env, user: Address) -> i128 {
-        let key = DataKey::Principal(user);
-        env.storage().persistent().get(&key).unwrap_or(0)
+        get_persistent(&env, &DataKey::Principal(user))
     }
-    
+
+    // Settled yield plus whatever the index owes since the last checkpoint
     pub fn get_yield(env: Env, user: Address) -> i128 {
-        let key = DataKey::Yield(user);
-        env.storage().persistent().get(&key).unwrap_or(0)
+        let (_, index) = pending_index(&env);
+        let checkpoint: i128 = env.storage().persistent().get(&DataKey::UserIndex(user.clone())).unwrap_or(index);
+        let weight = get_persistent(&env, &DataKey::Weight(user.clone()));
+        get_persistent(&env, &DataKey::Yield(user)) + earned_since(weight, index, checkpoint)
+    }
+
+    pub fn get_total_principal(env: Env) -> i128 {
+        get_instance(&env, &DataKey::TotalPrincipal)
+    }
+
+    pub fn get_total_yield(env: Env) -> i128 {
+        let (emitted, _) = pending_index(&env);
+        get_instance(&env, &DataKey::TotalYield) + emitted
+    }
+
+    pub fn get_reserve(env: Env) -> i128 {
+        let (emitted, _) = pending_index(&env);
+        get_instance(&env, &DataKey::RewardReserve) - emitted
+    }
+
+    pub fn reserve_runway(env: Env) -> u64 {
+        rewards::reserve_runway(&env)
+    }
+
+    pub fn current_apy(env: Env) -> u32 {
+        apy::current_apy(&env)
+    }
+
+    pub fn user_apy(env: Env, user: Address) -> u32 {
+        rewards::user_apy(&env, &user)
+    }
+
+    pub fn apy_history(env: Env) -> Vec<ApyChange> {
+        apy::history(&env)
+    }
+
+    pub fn apy_tiers(env: Env) -> Vec<ApyTier> {
+        apy::tiers(&env)
+    }
+
+    pub fn reward_index(env: Env) -> i128 {
+        let (_, index) = pending_index(&env);
+        index
     }
 }
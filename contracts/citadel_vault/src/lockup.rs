@@ -0,0 +1,47 @@
+use soroban_sdk::{contracttype, Address, Env};
+use crate::DataKey;
+
+// Share of the locked assets forfeited to the reward reserve on early exit
+pub const EARLY_EXIT_PENALTY_BPS: i128 = 1000; // 10%
+const SECONDS_IN_DAY: u64 = 86400;
+
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct LockPosition {
+    pub shares: i128,
+    pub unlock_ts: u64,
+    pub boost_bps: u32,
+}
+
+// APY boost per supported lock duration (10000 = 1x)
+pub fn boost_for_days(days: u32) -> u32 {
+    match days {
+        7 => 11000,
+        30 => 12500,
+        90 => 15000,
+        _ => panic!("Unsupported lock duration"),
+    }
+}
+
+pub fn get_lock(env: &Env, user: &Address) -> Option<LockPosition> {
+    env.storage().persistent().get(&DataKey::Lock(user.clone()))
+}
+
+pub fn open_lock(env: &Env, user: &Address, shares: i128, days: u32) -> LockPosition {
+    if get_lock(env, user).is_some() {
+        panic!("Lock already active");
+    }
+    let lock = LockPosition {
+        shares,
+        unlock_ts: env.ledger().timestamp() + (days as u64) * SECONDS_IN_DAY,
+        boost_bps: boost_for_days(days),
+    };
+    env.storage().persistent().set(&DataKey::Lock(user.clone()), &lock);
+    lock
+}
+
+pub fn close_lock(env: &Env, user: &Address) -> LockPosition {
+    let lock = get_lock(env, user).expect("No active lock");
+    env.storage().persistent().remove(&DataKey::Lock(user.clone()));
+    lock
+}
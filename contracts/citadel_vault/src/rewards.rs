@@ -0,0 +1,93 @@
+use soroban_sdk::{Address, Env};
+use fixed_math::{mul_div, Rounding};
+use crate::{apy, get_instance, get_persistent, lockup, DataKey};
+
+const SECONDS_IN_YEAR: i128 = 31536000;
+pub const INDEX_SCALE: i128 = 1_000_000_000_000;
+
+// Yield emitted since the last index update, and the index it leads to.
+// The index is cumulative yield per unit of weight at the base APY, and
+// never grows faster than the reward reserve can pay for.
+pub fn pending_index(env: &Env) -> (i128, i128) {
+    let index = get_instance(env, &DataKey::RewardIndex);
+    let now = env.ledger().timestamp();
+    let last: u64 = env.storage().instance().get(&DataKey::LastAccrual).unwrap_or(now);
+    if now <= last { return (0, index); }
+
+    // Yield = Weight * APY * (Elapsed / Year)
+    let elapsed = (now - last) as i128;
+    let mut delta = mul_div((apy::current_apy(env) as i128) * elapsed, INDEX_SCALE, 10000 * SECONDS_IN_YEAR, Rounding::Floor);
+    let total_weight = get_instance(env, &DataKey::TotalWeight);
+    if total_weight == 0 { return (0, index + delta); }
+
+    let reserve = get_instance(env, &DataKey::RewardReserve);
+    if mul_div(total_weight, delta, INDEX_SCALE, Rounding::Floor) > reserve {
+        delta = mul_div(reserve, INDEX_SCALE, total_weight, Rounding::Floor);
+    }
+    (mul_div(total_weight, delta, INDEX_SCALE, Rounding::Floor), index + delta)
+}
+
+// Moves the global index up to now, so rate changes only affect time after them.
+// Emitted yield is moved out of the reserve and owed to depositors.
+pub fn update_index(env: &Env) -> i128 {
+    let (emitted, index) = pending_index(env);
+    env.storage().instance().set(&DataKey::RewardIndex, &index);
+    env.storage().instance().set(&DataKey::LastAccrual, &env.ledger().timestamp());
+    if emitted > 0 {
+        env.storage().instance().set(&DataKey::RewardReserve, &(get_instance(env, &DataKey::RewardReserve) - emitted));
+        env.storage().instance().set(&DataKey::TotalYield, &(get_instance(env, &DataKey::TotalYield) + emitted));
+    }
+    index
+}
+
+// Seconds until the reserve runs dry at the current APY and weight
+pub fn reserve_runway(env: &Env) -> u64 {
+    let (emitted, _) = pending_index(env);
+    let reserve = get_instance(env, &DataKey::RewardReserve) - emitted;
+    let per_year = get_instance(env, &DataKey::TotalWeight) * (apy::current_apy(env) as i128);
+    if per_year == 0 { return u64::MAX; }
+
+    let runway = mul_div(reserve, 10000 * SECONDS_IN_YEAR, per_year, Rounding::Floor);
+    if runway > u64::MAX as i128 { u64::MAX } else { runway as u64 }
+}
+
+pub fn earned_since(weight: i128, index: i128, checkpoint: i128) -> i128 {
+    mul_div(weight, index - checkpoint, INDEX_SCALE, Rounding::Floor)
+}
+
+// Credits the user's share of the index growth since their checkpoint
+pub fn settle(env: &Env, user: &Address) -> i128 {
+    let index = update_index(env);
+    let index_key = DataKey::UserIndex(user.clone());
+    let checkpoint: i128 = env.storage().persistent().get(&index_key).unwrap_or(index);
+    env.storage().persistent().set(&index_key, &index);
+
+    let weight = get_persistent(env, &DataKey::Weight(user.clone()));
+    let earned = earned_since(weight, index, checkpoint);
+    if earned > 0 {
+        let yield_key = DataKey::Yield(user.clone());
+        env.storage().persistent().set(&yield_key, &(get_persistent(env, &yield_key) + earned));
+    }
+    earned
+}
+
+// Re-derives the user's weight from their liquid deposit tier and any lock boost,
+// call after `settle`
+pub fn refresh_weight(env: &Env, user: &Address) {
+    let deposit = crate::assets_of(env, user);
+    let mut weight = mul_div(deposit, apy::boost_for(env, deposit) as i128, 10000, Rounding::Floor);
+    if let Some(lock) = lockup::get_lock(env, user) {
+        weight += mul_div(crate::shares_to_assets(env, lock.shares), lock.boost_bps as i128, 10000, Rounding::Floor);
+    }
+
+    let weight_key = DataKey::Weight(user.clone());
+    let old = get_persistent(env, &weight_key);
+    env.storage().persistent().set(&weight_key, &weight);
+    env.storage().instance().set(&DataKey::TotalWeight, &(get_instance(env, &DataKey::TotalWeight) - old + weight));
+}
+
+// Effective APY on the user's liquid deposit
+pub fn user_apy(env: &Env, user: &Address) -> u32 {
+    let deposit = crate::assets_of(env, user);
+    mul_div(apy::current_apy(env) as i128, apy::boost_for(env, deposit) as i128, 10000, Rounding::Floor) as u32
+}
@@ -0,0 +1,504 @@
+#![cfg(test)]
+extern crate std;
+
+use crate::{to_assets, to_shares, to_shares_up, ApyChange, ApyTier, CitadelVault, CitadelVaultClient, VaultChange};
+use access_control::OPERATOR;
+use mock_lending_pool::{MockLendingPool, MockLendingPoolClient};
+use soroban_sdk::{
+    testutils::{Address as _, Ledger, MockAuth, MockAuthInvoke},
+    token, vec, Address, BytesN, Env, IntoVal, Vec,
+};
+
+const YEAR: u64 = 31_536_000;
+const DAY: u64 = 86_400;
+
+struct Setup<'a> {
+    env: Env,
+    vault: CitadelVaultClient<'a>,
+    pool: MockLendingPoolClient<'a>,
+    token: token::Client<'a>,
+    token_admin: token::StellarAssetClient<'a>,
+}
+
+// Queues `change` and executes it once the timelock allows
+fn apply(env: &Env, vault: &CitadelVaultClient, change: VaultChange) {
+    let id = vault.propose_change(&change);
+    env.ledger().set_timestamp(env.ledger().timestamp() + vault.timelock_delay());
+    vault.execute_change(&id);
+}
+
+// Vault with no yield source, principal stays idle
+fn setup_idle<'a>() -> Setup<'a> {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let issuer = Address::generate(&env);
+    let sac = env.register_stellar_asset_contract_v2(issuer);
+    let token = token::Client::new(&env, &sac.address());
+    let token_admin = token::StellarAssetClient::new(&env, &sac.address());
+
+    let pool = MockLendingPoolClient::new(&env, &env.register(MockLendingPool, (Address::generate(&env), sac.address())));
+
+    let vault = CitadelVaultClient::new(&env, &env.register(CitadelVault, (Address::generate(&env), sac.address())));
+
+    Setup { env, vault, pool, token, token_admin }
+}
+
+// Vault deploying principal to the mock lending pool
+fn setup<'a>() -> Setup<'a> {
+    let s = setup_idle();
+    apply(&s.env, &s.vault, VaultChange::YieldSource(Some(s.pool.address.clone())));
+    s
+}
+
+// A user holding `amount` tokens
+fn funded(s: &Setup, amount: i128) -> Address {
+    let user = Address::generate(&s.env);
+    s.token_admin.mint(&user, &amount);
+    user
+}
+
+fn fund_reserve(s: &Setup, amount: i128) {
+    s.vault.fund_reserve(&funded(s, amount), &amount);
+}
+
+fn wait(env: &Env, seconds: u64) {
+    env.ledger().set_timestamp(env.ledger().timestamp() + seconds);
+}
+
+#[test]
+fn withdraw_and_claim_keep_totals_consistent() {
+    let s = setup_idle();
+    let user = funded(&s, 1_000);
+    fund_reserve(&s, 1_000);
+    s.vault.deposit(&user, &1_000);
+    assert_eq!(s.vault.get_total_principal(), 1_000);
+
+    // 10% APY for a year
+    wait(&s.env, YEAR);
+    assert_eq!(s.vault.get_yield(&user), 100);
+    assert_eq!(s.vault.get_total_yield(), 100);
+
+    assert_eq!(s.vault.withdraw(&user, &400), 400);
+    assert_eq!(s.token.balance(&user), 400);
+    assert_eq!(s.vault.get_balance(&user), 600);
+    assert_eq!(s.vault.get_total_principal(), 600);
+    assert_eq!(s.vault.get_total_yield(), 100);
+
+    assert_eq!(s.vault.claim_yield(&user), 100);
+    assert_eq!(s.token.balance(&user), 500);
+    assert_eq!(s.vault.get_yield(&user), 0);
+    assert_eq!(s.vault.get_total_yield(), 0);
+    assert_eq!(s.vault.claim_yield(&user), 0);
+
+    // What the vault holds is exactly what it owes
+    assert_eq!(s.token.balance(&s.vault.address), s.vault.get_total_principal() + s.vault.get_reserve());
+}
+
+#[test]
+#[should_panic(expected = "Insufficient shares")]
+fn cannot_withdraw_more_than_deposited() {
+    let s = setup_idle();
+    let user = funded(&s, 1_000);
+    s.vault.deposit(&user, &1_000);
+    s.vault.withdraw(&user, &1_001);
+}
+
+#[test]
+fn empty_vault_prices_shares_one_to_one() {
+    let s = setup_idle();
+    assert_eq!(s.vault.preview_deposit(&1_000), 1_000);
+    assert_eq!(s.vault.preview_redeem(&1_000), 1_000);
+
+    let user = funded(&s, 1_000);
+    assert_eq!(s.vault.deposit(&user, &1_000), 1_000);
+    assert_eq!(s.vault.redeem(&user, &250), 250);
+    assert_eq!(s.vault.withdraw(&user, &250), 250);
+    assert_eq!(s.vault.total_shares(), 500);
+    assert_eq!(s.vault.total_assets(), 500);
+}
+
+#[test]
+fn share_math_rounds_towards_the_vault() {
+    // 3 shares backed by 10 assets, 1 share is worth 3.33
+    assert_eq!(to_shares(4, 3, 10), 1);    // Minting rounds down
+    assert_eq!(to_shares_up(4, 3, 10), 2); // Burning for exact assets rounds up
+    assert_eq!(to_assets(1, 3, 10), 3);    // Paying out rounds down
+
+    // Nothing to price against yet
+    assert_eq!(to_shares(7, 0, 0), 7);
+    assert_eq!(to_shares_up(7, 0, 0), 7);
+    assert_eq!(to_assets(7, 0, 0), 7);
+}
+
+#[test]
+fn late_depositor_earns_from_their_checkpoint() {
+    let s = setup_idle();
+    let early = funded(&s, 1_000);
+    let late = funded(&s, 1_000);
+    fund_reserve(&s, 10_000);
+    s.vault.deposit(&early, &1_000);
+
+    wait(&s.env, YEAR);
+    s.vault.deposit(&late, &1_000);
+    assert_eq!(s.vault.get_yield(&late), 0);
+
+    wait(&s.env, YEAR);
+    assert_eq!(s.vault.accrue_yield(&early), 200);
+    assert_eq!(s.vault.accrue_yield(&late), 100);
+    assert_eq!(s.vault.get_total_yield(), 300);
+}
+
+#[test]
+fn apy_change_only_applies_once_executed() {
+    let s = setup_idle();
+    let user = funded(&s, 1_000);
+    fund_reserve(&s, 10_000);
+    s.vault.deposit(&user, &1_000);
+
+    // Queued for a year, the old 10% still applies while it waits
+    let id = s.vault.propose_change(&VaultChange::Apy(2000));
+    wait(&s.env, YEAR);
+    assert_eq!(s.vault.get_yield(&user), 100);
+
+    s.vault.execute_change(&id);
+    assert_eq!(s.vault.get_yield(&user), 100);
+    wait(&s.env, YEAR);
+    assert_eq!(s.vault.get_yield(&user), 300);
+}
+
+fn tiers(env: &Env, tiers: &[(i128, u32)]) -> Vec<ApyTier> {
+    let mut out = Vec::new(env);
+    for (min_deposit, boost_bps) in tiers {
+        out.push_back(ApyTier { min_deposit: *min_deposit, boost_bps: *boost_bps });
+    }
+    out
+}
+
+#[test]
+fn deposits_earn_their_highest_tier() {
+    let s = setup_idle();
+    apply(&s.env, &s.vault, VaultChange::ApyTiers(tiers(&s.env, &[(1_000, 12_000), (5_000, 15_000)])));
+
+    let small = funded(&s, 500);
+    let mid = funded(&s, 1_000);
+    let large = funded(&s, 7_000);
+    s.vault.deposit(&small, &500);
+    s.vault.deposit(&mid, &1_000);
+    s.vault.deposit(&large, &7_000);
+
+    assert_eq!(s.vault.user_apy(&small), 1000);
+    assert_eq!(s.vault.user_apy(&mid), 1200);
+    assert_eq!(s.vault.user_apy(&large), 1500);
+
+    // Dropping below a threshold drops the tier
+    s.vault.withdraw(&mid, &1);
+    assert_eq!(s.vault.user_apy(&mid), 1000);
+}
+
+#[test]
+fn apy_history_records_every_change() {
+    let s = setup_idle();
+    assert_eq!(s.vault.apy_history(), vec![&s.env, ApyChange { timestamp: 0, apy_bps: 1000 }]);
+
+    apply(&s.env, &s.vault, VaultChange::Apy(1500));
+    let executed_at = s.env.ledger().timestamp();
+    apply(&s.env, &s.vault, VaultChange::Apy(800));
+
+    let history = s.vault.apy_history();
+    assert_eq!(history.len(), 3);
+    assert_eq!(history.get(1).unwrap(), ApyChange { timestamp: executed_at, apy_bps: 1500 });
+    assert_eq!(history.get(2).unwrap().apy_bps, 800);
+    assert_eq!(s.vault.current_apy(), 800);
+}
+
+#[test]
+#[should_panic(expected = "Tiers must be sorted by min_deposit")]
+fn unsorted_tiers_are_rejected() {
+    let s = setup_idle();
+    s.vault.propose_change(&VaultChange::ApyTiers(tiers(&s.env, &[(5_000, 15_000), (1_000, 12_000)])));
+}
+
+#[test]
+#[should_panic(expected = "Invalid tier boost")]
+fn tier_boost_below_one_is_rejected() {
+    let s = setup_idle();
+    s.vault.propose_change(&VaultChange::ApyTiers(tiers(&s.env, &[(1_000, 9_000)])));
+}
+
+#[test]
+#[should_panic(expected = "Invalid tier boost")]
+fn tier_boost_above_five_is_rejected() {
+    let s = setup_idle();
+    s.vault.propose_change(&VaultChange::ApyTiers(tiers(&s.env, &[(1_000, 50_001)])));
+}
+
+#[test]
+fn accrual_stops_when_the_reserve_runs_dry() {
+    let s = setup_idle();
+    let user = funded(&s, 1_000);
+    fund_reserve(&s, 50);
+    s.vault.deposit(&user, &1_000);
+
+    // 10% of 1000 would be 100 a year, the reserve only covers 50
+    wait(&s.env, YEAR);
+    assert_eq!(s.vault.get_yield(&user), 50);
+    assert_eq!(s.vault.get_reserve(), 0);
+
+    wait(&s.env, YEAR);
+    assert_eq!(s.vault.accrue_yield(&user), 50);
+    assert_eq!(s.vault.get_total_yield(), 50);
+    assert_eq!(s.vault.claim_yield(&user), 50);
+
+    // A top-up only pays for time after it
+    fund_reserve(&s, 1_000);
+    assert_eq!(s.vault.get_yield(&user), 0);
+    wait(&s.env, YEAR);
+    assert_eq!(s.vault.get_yield(&user), 100);
+}
+
+#[test]
+fn runway_is_reserve_over_emission_rate() {
+    let s = setup_idle();
+    fund_reserve(&s, 100);
+    assert_eq!(s.vault.reserve_runway(), u64::MAX);
+
+    // 1000 at 10% burns 100 a year
+    let user = funded(&s, 1_000);
+    s.vault.deposit(&user, &1_000);
+    assert_eq!(s.vault.reserve_runway(), YEAR);
+
+    wait(&s.env, YEAR / 4);
+    assert_eq!(s.vault.reserve_runway(), YEAR - YEAR / 4);
+}
+
+#[test]
+fn deposit_is_deployed_to_source() {
+    let s = setup();
+    let user = funded(&s, 1_000);
+
+    assert_eq!(s.vault.deposit(&user, &1_000), 1_000);
+    assert_eq!(s.token.balance(&s.vault.address), 0);
+    assert_eq!(s.token.balance(&s.pool.address), 1_000);
+    assert_eq!(s.pool.total_assets(&s.vault.address), 1_000);
+    assert_eq!(s.vault.total_assets(), 1_000);
+}
+
+#[test]
+fn source_interest_raises_share_price() {
+    let s = setup();
+    let alice = funded(&s, 1_000);
+    let bob = funded(&s, 1_000);
+    s.vault.deposit(&alice, &1_000);
+
+    // 10% interest on the vault's supply
+    s.token_admin.mint(&s.pool.address, &100);
+    s.pool.add_interest(&s.vault.address, &100);
+
+    assert_eq!(s.vault.total_assets(), 1_100);
+    assert_eq!(s.vault.preview_redeem(&1_000), 1_100);
+    assert_eq!(s.vault.preview_deposit(&1_100), 1_000);
+
+    // Later depositors buy in at the higher price
+    assert_eq!(s.vault.deposit(&bob, &1_000), 909);
+
+    assert_eq!(s.vault.redeem(&alice, &1_000), 1_100);
+    assert_eq!(s.token.balance(&alice), 1_100);
+}
+
+#[test]
+fn redeem_and_withdraw_round_towards_the_vault() {
+    let s = setup();
+    let user = funded(&s, 6);
+    s.vault.deposit(&user, &6);
+
+    // 6 shares backed by 10 assets
+    s.token_admin.mint(&s.pool.address, &4);
+    s.pool.add_interest(&s.vault.address, &4);
+
+    // 1 share is worth 1.67, redeeming pays 1
+    assert_eq!(s.vault.redeem(&user, &1), 1);
+    // 5 shares backed by 9 assets, 2 assets take 1.11 shares so 2 are burned
+    assert_eq!(s.vault.withdraw(&user, &2), 2);
+    assert_eq!(s.vault.get_shares(&user), 3);
+    assert_eq!(s.token.balance(&user), 3);
+}
+
+#[test]
+fn withdraw_recalls_from_source() {
+    let s = setup();
+    let user = funded(&s, 1_000);
+    s.vault.deposit(&user, &1_000);
+
+    s.vault.withdraw(&user, &400);
+    assert_eq!(s.token.balance(&user), 400);
+    assert_eq!(s.pool.total_assets(&s.vault.address), 600);
+    assert_eq!(s.vault.get_balance(&user), 600);
+}
+
+#[test]
+fn switching_source_off_recalls_principal() {
+    let s = setup();
+    let user = funded(&s, 1_000);
+    s.vault.deposit(&user, &1_000);
+
+    apply(&s.env, &s.vault, VaultChange::YieldSource(None));
+    assert_eq!(s.token.balance(&s.vault.address), 1_000);
+    assert_eq!(s.pool.total_assets(&s.vault.address), 0);
+    assert_eq!(s.vault.total_assets(), 1_000);
+}
+
+#[test]
+fn queued_change_is_visible_until_executed() {
+    let s = setup();
+    let id = s.vault.propose_change(&VaultChange::Apy(2000));
+
+    let queued = s.vault.queued_changes();
+    assert_eq!(queued.len(), 1);
+    assert_eq!(queued.get(0).unwrap().change, VaultChange::Apy(2000));
+    assert_eq!(s.vault.current_apy(), 1000);
+
+    s.env.ledger().set_timestamp(queued.get(0).unwrap().eta);
+    s.vault.execute_change(&id);
+    assert_eq!(s.vault.current_apy(), 2000);
+    assert!(s.vault.queued_changes().is_empty());
+}
+
+#[test]
+#[should_panic(expected = "Timelock not expired")]
+fn change_cannot_skip_delay() {
+    let s = setup();
+    let id = s.vault.propose_change(&VaultChange::Apy(2000));
+    s.vault.execute_change(&id);
+}
+
+#[test]
+#[should_panic(expected = "APY too high")]
+fn invalid_change_is_rejected_when_proposed() {
+    let s = setup();
+    s.vault.propose_change(&VaultChange::Apy(20_000));
+}
+
+#[test]
+fn operator_grant_waits_out_the_timelock() {
+    let s = setup();
+    let operator = Address::generate(&s.env);
+    assert!(s.vault.try_grant_role(&s.vault.admin(), &OPERATOR, &operator).is_err());
+
+    apply(&s.env, &s.vault, VaultChange::GrantRole(OPERATOR, operator.clone()));
+    assert!(s.vault.has_role(&OPERATOR, &operator));
+}
+
+#[test]
+fn deposit_reaches_the_source_with_only_the_users_auth() {
+    let s = setup();
+    let user = funded(&s, 1_000);
+
+    // The user signs for the deposit and the token pull it makes, the pool's
+    // pull from the vault is authorized by the vault itself
+    s.env.mock_auths(&[MockAuth {
+        address: &user,
+        invoke: &MockAuthInvoke {
+            contract: &s.vault.address,
+            fn_name: "deposit",
+            args: (&user, 1_000i128).into_val(&s.env),
+            sub_invokes: &[MockAuthInvoke {
+                contract: &s.token.address,
+                fn_name: "transfer",
+                args: (&user, &s.vault.address, 1_000i128).into_val(&s.env),
+                sub_invokes: &[],
+            }],
+        },
+    }]);
+    s.vault.deposit(&user, &1_000);
+    assert_eq!(s.token.balance(&s.pool.address), 1_000);
+    assert_eq!(s.pool.total_assets(&s.vault.address), 1_000);
+}
+
+#[test]
+fn deposit_without_the_users_auth_fails() {
+    let s = setup();
+    let user = funded(&s, 1_000);
+
+    s.env.set_auths(&[]);
+    assert!(s.vault.try_deposit(&user, &1_000).is_err());
+    assert_eq!(s.token.balance(&user), 1_000);
+}
+
+#[test]
+fn only_the_pool_admin_adds_interest() {
+    let s = setup();
+    s.env.set_auths(&[]);
+    assert!(s.pool.try_add_interest(&s.vault.address, &100).is_err());
+}
+
+#[test]
+#[should_panic(expected = "Lock not matured")]
+fn lock_cannot_be_unlocked_early() {
+    let s = setup_idle();
+    let user = funded(&s, 1_000);
+    s.vault.deposit_locked(&user, &1_000, &30);
+
+    wait(&s.env, 30 * DAY - 1);
+    s.vault.unlock(&user);
+}
+
+#[test]
+fn matured_lock_turns_liquid() {
+    let s = setup_idle();
+    let user = funded(&s, 1_000);
+    let lock = s.vault.deposit_locked(&user, &1_000, &7);
+    assert_eq!(lock.unlock_ts, 7 * DAY);
+    assert_eq!(s.vault.get_shares(&user), 0);
+
+    wait(&s.env, 7 * DAY);
+    assert_eq!(s.vault.unlock(&user), 1_000);
+    assert_eq!(s.vault.get_shares(&user), 1_000);
+    assert_eq!(s.vault.get_lock(&user), None);
+}
+
+#[test]
+fn breaking_a_lock_feeds_the_penalty_to_the_reserve() {
+    let s = setup_idle();
+    let user = funded(&s, 1_000);
+    s.vault.deposit_locked(&user, &1_000, &30);
+
+    assert_eq!(s.vault.break_lock(&user), 900);
+    assert_eq!(s.token.balance(&user), 900);
+    assert_eq!(s.vault.get_reserve(), 100);
+    assert_eq!(s.vault.get_total_principal(), 0);
+    assert_eq!(s.vault.get_lock(&user), None);
+}
+
+#[test]
+fn locked_deposits_earn_boosted_yield() {
+    let s = setup_idle();
+    let liquid = funded(&s, 1_000);
+    let locked = funded(&s, 1_000);
+    fund_reserve(&s, 1_000);
+    s.vault.deposit(&liquid, &1_000);
+    s.vault.deposit_locked(&locked, &1_000, &90);
+
+    // A fifth of a year at 10%, the 90 day lock earns 1.5x
+    wait(&s.env, YEAR / 5);
+    assert_eq!(s.vault.get_yield(&liquid), 20);
+    assert_eq!(s.vault.get_yield(&locked), 30);
+}
+
+#[test]
+#[should_panic(expected = "Lock already active")]
+fn one_lock_per_user() {
+    let s = setup_idle();
+    let user = funded(&s, 2_000);
+    s.vault.deposit_locked(&user, &1_000, &7);
+    s.vault.deposit_locked(&user, &1_000, &30);
+}
+
+#[test]
+fn only_the_admin_upgrades() {
+    let s = setup_idle();
+    assert_eq!(s.vault.version(), 1);
+
+    s.env.set_auths(&[]);
+    assert!(s.vault.try_upgrade(&BytesN::from_array(&s.env, &[7; 32])).is_err());
+}
@@ -0,0 +1,80 @@
+use soroban_sdk::{
+    auth::{ContractContext, InvokerContractAuthEntry, SubContractInvocation},
+    contractclient, vec, Address, Env, IntoVal, Symbol,
+};
+use crate::{get_instance, DataKey};
+
+// Interface a lending protocol adapter exposes to the vault. Balances are
+// tracked per `owner`, so one source can serve several vaults.
+#[contractclient(name = "YieldSourceClient")]
+pub trait YieldSource {
+    // Pulls `amount` of the underlying token from `from`
+    fn deposit(env: Env, from: Address, amount: i128);
+    // Sends `amount` of the underlying token back to `to`
+    fn withdraw(env: Env, to: Address, amount: i128);
+    // Everything `owner` could withdraw right now, interest included
+    fn total_assets(env: Env, owner: Address) -> i128;
+}
+
+pub fn get_source(env: &Env) -> Option<Address> {
+    env.storage().instance().get(&DataKey::YieldSource)
+}
+
+fn set_idle(env: &Env, amount: i128) {
+    env.storage().instance().set(&DataKey::IdleAssets, &amount);
+}
+
+// Principal held by the vault plus everything deployed to the source
+pub fn total_assets(env: &Env) -> i128 {
+    let idle = get_instance(env, &DataKey::IdleAssets);
+    match get_source(env) {
+        Some(source) => idle + YieldSourceClient::new(env, &source).total_assets(&env.current_contract_address()),
+        None => idle,
+    }
+}
+
+// Moves all idle principal into the source
+pub fn push_idle(env: &Env) {
+    let Some(source) = get_source(env) else { return };
+    let amount = get_instance(env, &DataKey::IdleAssets);
+    if amount == 0 { return; }
+
+    // The source pulls the tokens, which needs the vault's auth one call deeper
+    let token: Address = env.storage().instance().get(&DataKey::Token).expect("Vault not initialized");
+    let vault = env.current_contract_address();
+    env.authorize_as_current_contract(vec![
+        env,
+        InvokerContractAuthEntry::Contract(SubContractInvocation {
+            context: ContractContext {
+                contract: token,
+                fn_name: Symbol::new(env, "transfer"),
+                args: (vault.clone(), source.clone(), amount).into_val(env),
+            },
+            sub_invocations: vec![env],
+        }),
+    ]);
+    YieldSourceClient::new(env, &source).deposit(&vault, &amount);
+    set_idle(env, 0);
+}
+
+// Makes sure at least `amount` sits idle in the vault, recalling from the source if needed
+pub fn ensure_idle(env: &Env, amount: i128) {
+    let idle = get_instance(env, &DataKey::IdleAssets);
+    if idle >= amount { return; }
+    let Some(source) = get_source(env) else { return };
+
+    let needed = amount - idle;
+    YieldSourceClient::new(env, &source).withdraw(&env.current_contract_address(), &needed);
+    set_idle(env, amount);
+}
+
+// Recalls everything from the current source
+pub fn recall_all(env: &Env) {
+    let Some(source) = get_source(env) else { return };
+    let client = YieldSourceClient::new(env, &source);
+    let deployed = client.total_assets(&env.current_contract_address());
+    if deployed > 0 {
+        client.withdraw(&env.current_contract_address(), &deployed);
+        set_idle(env, get_instance(env, &DataKey::IdleAssets) + deployed);
+    }
+}
@@ -0,0 +1,286 @@
+#![no_std]
+// Fixed-point math shared by the game contracts. Values are i128 scaled by
+// WAD (1e18). Lossy operations take a `Rounding` so each caller can round in
+// the protocol's favour: payouts to players `Floor`, amounts owed to the
+// protocol `Ceil`.
+
+pub const WAD: i128 = 1_000_000_000_000_000_000;
+
+const LN_2: i128 = 693_147_180_559_945_309; // ln(2) * WAD
+const LOG10_2: i128 = 301_029_995_663_981_195; // log10(2) * WAD
+
+// exp(x) * WAD stops fitting in an i128 just above 46, and rounds to 0 below -42
+const MAX_EXP_ARG: i128 = 46 * WAD;
+const MIN_EXP_ARG: i128 = -42 * WAD;
+
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum Rounding {
+    Floor,
+    Ceil,
+}
+
+// Full 256-bit product of two u128s as (high, low)
+fn mul_wide(a: u128, b: u128) -> (u128, u128) {
+    const MASK: u128 = u64::MAX as u128;
+    let (a_lo, a_hi) = (a & MASK, a >> 64);
+    let (b_lo, b_hi) = (b & MASK, b >> 64);
+
+    let ll = a_lo * b_lo;
+    let lh = a_lo * b_hi;
+    let hl = a_hi * b_lo;
+    let hh = a_hi * b_hi;
+
+    let mid = (ll >> 64) + (lh & MASK) + (hl & MASK);
+    let lo = (ll & MASK) | (mid << 64);
+    let hi = hh + (lh >> 64) + (hl >> 64) + (mid >> 64);
+    (hi, lo)
+}
+
+// (high, low) / d as (quotient, remainder), None if the quotient needs more than 128 bits
+fn div_wide(hi: u128, lo: u128, d: u128) -> Option<(u128, u128)> {
+    if hi == 0 {
+        return Some((lo / d, lo % d));
+    }
+    if hi >= d {
+        return None;
+    }
+
+    let mut rem = hi;
+    let mut quot: u128 = 0;
+    for i in (0..128).rev() {
+        let carry = rem >> 127;
+        rem = (rem << 1) | ((lo >> i) & 1);
+        quot <<= 1;
+        if carry == 1 || rem >= d {
+            rem = rem.wrapping_sub(d);
+            quot |= 1;
+        }
+    }
+    Some((quot, rem))
+}
+
+// a * b / denominator without intermediate overflow, None if the result does not fit
+pub fn checked_mul_div(a: i128, b: i128, denominator: i128, rounding: Rounding) -> Option<i128> {
+    if denominator == 0 {
+        return None;
+    }
+    let negative = (a < 0) ^ (b < 0) ^ (denominator < 0);
+    let (hi, lo) = mul_wide(a.unsigned_abs(), b.unsigned_abs());
+    let (mut quot, rem) = div_wide(hi, lo, denominator.unsigned_abs())?;
+
+    // Truncation moved the result towards zero, step away from it where asked
+    let away_from_zero = match rounding {
+        Rounding::Floor => negative,
+        Rounding::Ceil => !negative,
+    };
+    if rem != 0 && away_from_zero {
+        quot = quot.checked_add(1)?;
+    }
+
+    if negative {
+        if quot > i128::MIN.unsigned_abs() {
+            return None;
+        }
+        Some((quot as i128).wrapping_neg())
+    } else {
+        i128::try_from(quot).ok()
+    }
+}
+
+pub fn mul_div(a: i128, b: i128, denominator: i128, rounding: Rounding) -> i128 {
+    checked_mul_div(a, b, denominator, rounding).expect("fixed_math: mul_div overflow")
+}
+
+pub fn mul_wad(a: i128, b: i128, rounding: Rounding) -> i128 {
+    mul_div(a, b, WAD, rounding)
+}
+
+pub fn div_wad(a: i128, b: i128, rounding: Rounding) -> i128 {
+    mul_div(a, WAD, b, rounding)
+}
+
+// log2 of a positive WAD value, by repeated squaring of the mantissa
+pub fn log2(x: i128) -> i128 {
+    if x <= 0 {
+        panic!("fixed_math: log of non-positive value");
+    }
+
+    // Integer part: normalise x into [1, 2)
+    let mut result: i128 = 0;
+    let mut y = x;
+    let int_part = (x / WAD) as u128;
+    if int_part > 1 {
+        let n = 127 - int_part.leading_zeros();
+        y >>= n;
+        result = (n as i128) * WAD;
+    }
+    while y >= 2 * WAD {
+        y /= 2;
+        result += WAD;
+    }
+    while y < WAD {
+        y *= 2;
+        result -= WAD;
+    }
+
+    // Fractional part, one bit per squaring
+    let mut bit = WAD / 2;
+    while bit > 0 {
+        y = y * y / WAD;
+        if y >= 2 * WAD {
+            y /= 2;
+            result += bit;
+        }
+        bit /= 2;
+    }
+    result
+}
+
+pub fn ln(x: i128) -> i128 {
+    log2(x) * LN_2 / WAD
+}
+
+pub fn log10(x: i128) -> i128 {
+    log2(x) * LOG10_2 / WAD
+}
+
+// e^x, None when the result does not fit in a WAD i128
+pub fn checked_exp(x: i128) -> Option<i128> {
+    if x > MAX_EXP_ARG {
+        return None;
+    }
+    if x < MIN_EXP_ARG {
+        return Some(0);
+    }
+
+    // e^x = 2^k * e^r with r in [0, ln 2)
+    let k = x.div_euclid(LN_2);
+    let r = x - k * LN_2;
+
+    let mut term = WAD;
+    let mut sum = WAD;
+    let mut i = 1;
+    while term != 0 {
+        term = term * r / (i * WAD);
+        sum += term;
+        i += 1;
+    }
+
+    if k >= 0 {
+        sum.checked_mul(1i128 << k)
+    } else if k <= -127 {
+        Some(0)
+    } else {
+        Some(sum >> (-k))
+    }
+}
+
+pub fn exp(x: i128) -> i128 {
+    checked_exp(x).expect("fixed_math: exp overflow")
+}
+
+// x^y for positive x and any WAD exponent
+pub fn pow(x: i128, y: i128) -> i128 {
+    if x == 0 {
+        return if y == 0 { WAD } else { 0 };
+    }
+    exp(mul_wad(y, ln(x), Rounding::Floor))
+}
+
+// x^n for an integer exponent, exact up to the rounding of each multiplication
+pub fn pow_int(x: i128, n: u32, rounding: Rounding) -> i128 {
+    let mut result = WAD;
+    let mut base = x;
+    let mut n = n;
+    while n > 0 {
+        if n & 1 == 1 {
+            result = mul_wad(result, base, rounding);
+        }
+        n >>= 1;
+        if n > 0 {
+            base = mul_wad(base, base, rounding);
+        }
+    }
+    result
+}
+
+// 1 / (1 + e^-x), saturating to 0 and WAD at the extremes
+pub fn sigmoid(x: i128, rounding: Rounding) -> i128 {
+    if x <= MIN_EXP_ARG {
+        return 0;
+    }
+    if x >= -MIN_EXP_ARG {
+        return WAD;
+    }
+    mul_div(WAD, WAD, WAD + exp(-x), rounding)
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    const EPSILON: i128 = 1_000; // 1e-15
+
+    fn assert_close(actual: i128, expected: i128) {
+        let diff = (actual - expected).abs();
+        assert!(diff <= EPSILON, "{} != {} (diff {})", actual, expected, diff);
+    }
+
+    #[test]
+    fn mul_div_rounds_both_ways() {
+        assert_eq!(mul_div(10, 1, 3, Rounding::Floor), 3);
+        assert_eq!(mul_div(10, 1, 3, Rounding::Ceil), 4);
+        assert_eq!(mul_div(-10, 1, 3, Rounding::Floor), -4);
+        assert_eq!(mul_div(-10, 1, 3, Rounding::Ceil), -3);
+        assert_eq!(mul_div(9, 1, 3, Rounding::Ceil), 3);
+    }
+
+    #[test]
+    fn mul_div_survives_wide_intermediates() {
+        let big = i128::MAX / 2;
+        assert_eq!(mul_div(big, 6, 3, Rounding::Floor), big * 2);
+        assert_eq!(mul_div(big, big, big, Rounding::Floor), big);
+        assert_eq!(checked_mul_div(i128::MAX, 2, 1, Rounding::Floor), None);
+        assert_eq!(checked_mul_div(1, 1, 0, Rounding::Floor), None);
+    }
+
+    #[test]
+    fn logs() {
+        assert_eq!(log2(WAD), 0);
+        assert_eq!(log2(8 * WAD), 3 * WAD);
+        assert_eq!(log2(WAD / 4), -2 * WAD);
+        assert_close(ln(2 * WAD), LN_2);
+        assert_close(ln(10 * WAD), 2_302_585_092_994_045_684);
+        assert_close(log10(2 * WAD), LOG10_2);
+        assert_close(log10(1000 * WAD), 3 * WAD);
+    }
+
+    #[test]
+    fn exps() {
+        assert_eq!(exp(0), WAD);
+        assert_close(exp(WAD), 2_718_281_828_459_045_235);
+        assert_close(exp(-WAD), 367_879_441_171_442_321);
+        assert_close(exp(ln(5 * WAD)), 5 * WAD);
+        assert_eq!(exp(-50 * WAD), 0);
+        assert_eq!(checked_exp(47 * WAD), None);
+        assert!(checked_exp(MAX_EXP_ARG).is_some());
+    }
+
+    #[test]
+    fn powers() {
+        assert_eq!(pow_int(2 * WAD, 10, Rounding::Floor), 1024 * WAD);
+        assert_eq!(pow_int(3 * WAD, 0, Rounding::Floor), WAD);
+        assert_close(pow(4 * WAD, WAD / 2), 2 * WAD);
+        assert_close(pow(2 * WAD, 3 * WAD), 8 * WAD);
+    }
+
+    #[test]
+    fn sigmoid_matches_battle_logic() {
+        // calculateWinProbability uses 1 / (1 + e^(-0.1 * diff))
+        assert_eq!(sigmoid(0, Rounding::Floor), WAD / 2);
+        assert_close(sigmoid(WAD, Rounding::Floor), 731_058_578_630_004_879);
+        assert_close(sigmoid(-WAD, Rounding::Floor), 268_941_421_369_995_120);
+        assert_eq!(sigmoid(-100 * WAD, Rounding::Floor), 0);
+        assert_eq!(sigmoid(100 * WAD, Rounding::Floor), WAD);
+    }
+}
@@ -0,0 +1,60 @@
+#![no_std]
+use soroban_sdk::{contract, contractimpl, contracttype, token, Address, Env};
+
+// Stand-in for a Stellar lending protocol, implementing the CitadelVault
+// `YieldSource` interface. Interest is injected by hand by the pool admin.
+
+#[contracttype]
+#[derive(Clone)]
+pub enum DataKey {
+    Admin,            // May credit interest
+    Token,
+    Balance(Address), // Owner -> Supplied amount plus interest
+}
+
+#[contract]
+pub struct MockLendingPool;
+
+fn token_client(env: &Env) -> token::Client<'_> {
+    let token_id: Address = env.storage().instance().get(&DataKey::Token).expect("Pool not initialized");
+    token::Client::new(env, &token_id)
+}
+
+fn balance(env: &Env, owner: &Address) -> i128 {
+    env.storage().persistent().get(&DataKey::Balance(owner.clone())).unwrap_or(0)
+}
+
+#[contractimpl]
+impl MockLendingPool {
+    pub fn __constructor(env: Env, admin: Address, token: Address) {
+        env.storage().instance().set(&DataKey::Admin, &admin);
+        env.storage().instance().set(&DataKey::Token, &token);
+    }
+
+    pub fn deposit(env: Env, from: Address, amount: i128) {
+        from.require_auth();
+        token_client(&env).transfer(&from, env.current_contract_address(), &amount);
+        env.storage().persistent().set(&DataKey::Balance(from.clone()), &(balance(&env, &from) + amount));
+    }
+
+    pub fn withdraw(env: Env, to: Address, amount: i128) {
+        to.require_auth();
+        let current = balance(&env, &to);
+        if amount > current {
+            panic!("Insufficient pool balance");
+        }
+        env.storage().persistent().set(&DataKey::Balance(to.clone()), &(current - amount));
+        token_client(&env).transfer(&env.current_contract_address(), &to, &amount);
+    }
+
+    pub fn total_assets(env: Env, owner: Address) -> i128 {
+        balance(&env, &owner)
+    }
+
+    // Admin: credits interest to `owner`, the tokens backing it must already be in the pool
+    pub fn add_interest(env: Env, owner: Address, amount: i128) {
+        let admin: Address = env.storage().instance().get(&DataKey::Admin).expect("Pool not initialized");
+        admin.require_auth();
+        env.storage().persistent().set(&DataKey::Balance(owner.clone()), &(balance(&env, &owner) + amount));
+    }
+}
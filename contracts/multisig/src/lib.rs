@@ -0,0 +1,194 @@
+#![no_std]
+use soroban_sdk::{contract, contractevent, contractimpl, contracttype, Address, Env, Symbol, Val, Vec};
+
+mod test;
+
+// M-of-N wallet meant to sit in the admin seat of the game contracts and the
+// treasury. Any signer can propose a call, it runs once `threshold` signers
+// have approved it. The target sees this contract as the direct invoker, so
+// its `admin.require_auth()` passes without further signatures.
+//
+// To take over a contract, its admin calls `transfer_admin(multisig)` and the
+// signers then propose and execute `accept_admin` on it.
+
+#[contracttype]
+#[derive(Clone)]
+pub enum DataKey {
+    Signers,            // Vec<Address>
+    Threshold,          // Approvals needed to execute
+    NextProposalId,
+    Proposal(u64),      // Id -> Proposal
+}
+
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub enum Call {
+    // Call `function` on `contract` with `args`
+    Invoke(Address, Symbol, Vec<Val>),
+    // Replace the signer set and threshold. Contracts can't call themselves,
+    // so this is the only way to change them.
+    SetSigners(Vec<Address>, u32),
+}
+
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct Proposal {
+    pub call: Call,
+    pub approvals: Vec<Address>,
+    pub executed: bool,
+}
+
+#[contractevent(data_format = "single-value")]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct Proposed {
+    #[topic]
+    pub id: u64,
+    pub proposer: Address,
+}
+
+#[contractevent(data_format = "single-value")]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct Approved {
+    #[topic]
+    pub id: u64,
+    pub signer: Address,
+}
+
+#[contractevent(data_format = "single-value")]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct Executed {
+    #[topic]
+    pub id: u64,
+    pub approvals: u32,
+}
+
+const PROPOSAL_TTL: u32 = 518400; // ~30 days of ledgers
+
+#[contract]
+pub struct Multisig;
+
+fn signers(env: &Env) -> Vec<Address> {
+    env.storage().instance().get(&DataKey::Signers).expect("Multisig not initialized")
+}
+
+fn threshold(env: &Env) -> u32 {
+    env.storage().instance().get(&DataKey::Threshold).expect("Multisig not initialized")
+}
+
+fn set_signers(env: &Env, signers: &Vec<Address>, threshold: u32) {
+    if threshold == 0 || threshold > signers.len() {
+        panic!("Invalid threshold");
+    }
+    for (i, signer) in signers.iter().enumerate() {
+        if signers.first_index_of(&signer) != Some(i as u32) {
+            panic!("Duplicate signer");
+        }
+    }
+    env.storage().instance().set(&DataKey::Signers, signers);
+    env.storage().instance().set(&DataKey::Threshold, &threshold);
+}
+
+fn require_signer(env: &Env, signer: &Address) {
+    signer.require_auth();
+    if !signers(env).contains(signer) {
+        panic!("Not a signer");
+    }
+}
+
+fn get_proposal(env: &Env, id: u64) -> Proposal {
+    env.storage().persistent().get(&DataKey::Proposal(id)).expect("Proposal not found")
+}
+
+fn set_proposal(env: &Env, id: u64, proposal: &Proposal) {
+    let key = DataKey::Proposal(id);
+    env.storage().persistent().set(&key, proposal);
+    env.storage().persistent().extend_ttl(&key, PROPOSAL_TTL, PROPOSAL_TTL);
+}
+
+// Approvals from signers removed since they approved don't count
+fn approval_count(env: &Env, proposal: &Proposal) -> u32 {
+    let signers = signers(env);
+    proposal.approvals.iter().filter(|a| signers.contains(a)).count() as u32
+}
+
+#[contractimpl]
+impl Multisig {
+    pub fn __constructor(env: Env, signers: Vec<Address>, threshold: u32) {
+        set_signers(&env, &signers, threshold);
+    }
+
+    // Signer: returns the proposal id, the proposer's approval is included
+    pub fn propose(env: Env, proposer: Address, call: Call) -> u64 {
+        require_signer(&env, &proposer);
+        let id: u64 = env.storage().instance().get(&DataKey::NextProposalId).unwrap_or(0);
+        env.storage().instance().set(&DataKey::NextProposalId, &(id + 1));
+
+        let proposal = Proposal { call, approvals: Vec::from_array(&env, [proposer.clone()]), executed: false };
+        set_proposal(&env, id, &proposal);
+        Proposed { id, proposer }.publish(&env);
+        id
+    }
+
+    pub fn approve(env: Env, signer: Address, id: u64) {
+        require_signer(&env, &signer);
+        let mut proposal = get_proposal(&env, id);
+        if proposal.executed {
+            panic!("Proposal already executed");
+        }
+        if proposal.approvals.contains(&signer) {
+            panic!("Already approved");
+        }
+        proposal.approvals.push_back(signer.clone());
+        set_proposal(&env, id, &proposal);
+        Approved { id, signer }.publish(&env);
+    }
+
+    pub fn revoke_approval(env: Env, signer: Address, id: u64) {
+        require_signer(&env, &signer);
+        let mut proposal = get_proposal(&env, id);
+        if proposal.executed {
+            panic!("Proposal already executed");
+        }
+        let i = proposal.approvals.first_index_of(&signer).expect("Not approved");
+        proposal.approvals.remove(i);
+        set_proposal(&env, id, &proposal);
+    }
+
+    // Anyone can trigger a proposal once it has enough approvals
+    pub fn execute(env: Env, id: u64) -> Val {
+        let mut proposal = get_proposal(&env, id);
+        if proposal.executed {
+            panic!("Proposal already executed");
+        }
+        let approvals = approval_count(&env, &proposal);
+        if approvals < threshold(&env) {
+            panic!("Not enough approvals");
+        }
+
+        // Mark first so the call can't re-enter and run it twice
+        proposal.executed = true;
+        set_proposal(&env, id, &proposal);
+
+        let result = match proposal.call {
+            Call::Invoke(contract, function, args) => env.invoke_contract::<Val>(&contract, &function, args),
+            Call::SetSigners(signers, threshold) => {
+                set_signers(&env, &signers, threshold);
+                Val::VOID.to_val()
+            }
+        };
+        Executed { id, approvals }.publish(&env);
+        result
+    }
+
+    pub fn get_proposal(env: Env, id: u64) -> Proposal {
+        get_proposal(&env, id)
+    }
+
+    pub fn signers(env: Env) -> Vec<Address> {
+        signers(&env)
+    }
+
+    pub fn threshold(env: Env) -> u32 {
+        threshold(&env)
+    }
+}
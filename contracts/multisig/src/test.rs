@@ -0,0 +1,148 @@
+#![cfg(test)]
+extern crate std;
+
+use crate::{Call, Multisig, MultisigClient};
+use citadel_vault::{CitadelVault, CitadelVaultClient, VaultChange};
+use soroban_sdk::{
+    contract, contractimpl, symbol_short, testutils::{Address as _, Ledger}, vec, Address, Env, IntoVal, Symbol,
+};
+
+// Stands in for YieldRaiders or CitadelVault: one admin-gated setter
+#[contract]
+struct Target;
+
+#[contractimpl]
+impl Target {
+    pub fn init(env: Env, admin: Address) {
+        env.storage().instance().set(&symbol_short!("admin"), &admin);
+    }
+
+    pub fn set_value(env: Env, value: u32) {
+        let admin: Address = env.storage().instance().get(&symbol_short!("admin")).unwrap();
+        admin.require_auth();
+        env.storage().instance().set(&symbol_short!("value"), &value);
+    }
+
+    pub fn value(env: Env) -> u32 {
+        env.storage().instance().get(&symbol_short!("value")).unwrap_or(0)
+    }
+}
+
+struct Setup<'a> {
+    env: Env,
+    multisig: MultisigClient<'a>,
+    target: TargetClient<'a>,
+    signers: [Address; 3],
+}
+
+// 2-of-3 multisig sitting in the target's admin seat
+fn setup<'a>() -> Setup<'a> {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let signers = [Address::generate(&env), Address::generate(&env), Address::generate(&env)];
+    let members = vec![&env, signers[0].clone(), signers[1].clone(), signers[2].clone()];
+    let multisig = MultisigClient::new(&env, &env.register(Multisig, (members, 2u32)));
+
+    let target = TargetClient::new(&env, &env.register(Target, ()));
+    target.init(&multisig.address);
+
+    Setup { env, multisig, target, signers }
+}
+
+fn set_value_call(s: &Setup, value: u32) -> Call {
+    Call::Invoke(s.target.address.clone(), Symbol::new(&s.env, "set_value"), vec![&s.env, value.into_val(&s.env)])
+}
+
+#[test]
+fn executes_admin_call_after_threshold() {
+    let s = setup();
+    let id = s.multisig.propose(&s.signers[0], &set_value_call(&s, 7));
+    s.multisig.approve(&s.signers[1], &id);
+
+    // Only the multisig itself authorizes the target call
+    s.env.set_auths(&[]);
+    s.multisig.execute(&id);
+    assert_eq!(s.target.value(), 7);
+    assert!(s.multisig.get_proposal(&id).executed);
+}
+
+#[test]
+#[should_panic(expected = "Not enough approvals")]
+fn needs_threshold_approvals() {
+    let s = setup();
+    let id = s.multisig.propose(&s.signers[0], &set_value_call(&s, 7));
+    s.multisig.execute(&id);
+}
+
+#[test]
+#[should_panic(expected = "Proposal already executed")]
+fn executes_once() {
+    let s = setup();
+    let id = s.multisig.propose(&s.signers[0], &set_value_call(&s, 7));
+    s.multisig.approve(&s.signers[1], &id);
+    s.multisig.execute(&id);
+    s.multisig.execute(&id);
+}
+
+#[test]
+#[should_panic(expected = "Not a signer")]
+fn outsiders_cannot_propose() {
+    let s = setup();
+    let outsider = Address::generate(&s.env);
+    s.multisig.propose(&outsider, &set_value_call(&s, 7));
+}
+
+#[test]
+fn signer_set_changes_through_a_proposal() {
+    let s = setup();
+    let newcomer = Address::generate(&s.env);
+    let signers = vec![&s.env, s.signers[0].clone(), s.signers[2].clone(), newcomer];
+
+    // Approved by a signer who is about to be removed
+    let pending = s.multisig.propose(&s.signers[0], &set_value_call(&s, 7));
+    s.multisig.approve(&s.signers[1], &pending);
+
+    let id = s.multisig.propose(&s.signers[0], &Call::SetSigners(signers.clone(), 2));
+    s.multisig.approve(&s.signers[2], &id);
+    s.multisig.execute(&id);
+    assert_eq!(s.multisig.signers(), signers);
+
+    // The removed signer's approval no longer counts
+    assert!(s.multisig.try_execute(&pending).is_err());
+    s.multisig.approve(&s.signers[2], &pending);
+    s.multisig.execute(&pending);
+    assert_eq!(s.target.value(), 7);
+}
+
+// Proposes `call`, gets a second approval and executes it with no mocked auths
+fn run(s: &Setup, call: Call) {
+    s.env.mock_all_auths();
+    let id = s.multisig.propose(&s.signers[0], &call);
+    s.multisig.approve(&s.signers[2], &id);
+    s.env.set_auths(&[]);
+    s.multisig.execute(&id);
+}
+
+#[test]
+fn takes_over_and_administers_the_vault() {
+    let s = setup();
+    let token = s.env.register_stellar_asset_contract_v2(Address::generate(&s.env)).address();
+    let admin = Address::generate(&s.env);
+    let vault = CitadelVaultClient::new(&s.env, &s.env.register(CitadelVault, (admin.clone(), token)));
+
+    // The current admin nominates the multisig, which accepts through a proposal
+    vault.transfer_admin(&s.multisig.address);
+    run(&s, Call::Invoke(vault.address.clone(), Symbol::new(&s.env, "accept_admin"), vec![&s.env]));
+    assert_eq!(vault.admin(), s.multisig.address);
+
+    // Admin calls now need the signers, here an APY change through the vault's timelock
+    let change = VaultChange::Apy(2000);
+    run(&s, Call::Invoke(vault.address.clone(), Symbol::new(&s.env, "propose_change"), vec![&s.env, change.into_val(&s.env)]));
+    let queued = vault.queued_changes().get(0).unwrap();
+    assert_eq!(queued.change, VaultChange::Apy(2000));
+
+    s.env.ledger().set_timestamp(queued.eta);
+    run(&s, Call::Invoke(vault.address.clone(), Symbol::new(&s.env, "execute_change"), vec![&s.env, queued.id.into_val(&s.env)]));
+    assert_eq!(vault.current_apy(), 2000);
+}
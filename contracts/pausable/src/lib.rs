@@ -0,0 +1,169 @@
+#![no_std]
+// Circuit breaker shared by the game contracts. Each contract names its
+// pausable features with a Symbol (e.g. "raid", "withdraw") so an exploit in
+// one entrypoint can be stopped without freezing the rest. The admin or a
+// holder of the GUARDIAN role can pause, only the admin can unpause.
+
+use soroban_sdk::{contractevent, contracttype, Address, Env, Symbol};
+use access_control::GUARDIAN;
+
+#[contracttype]
+#[derive(Clone)]
+pub enum PauseKey {
+    Paused(Symbol),  // Feature -> true while paused
+}
+
+#[contractevent(data_format = "single-value")]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct Paused {
+    #[topic]
+    pub feature: Symbol,
+    pub by: Address,
+}
+
+#[contractevent(data_format = "single-value")]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct Unpaused {
+    #[topic]
+    pub feature: Symbol,
+    pub by: Address,
+}
+
+pub fn is_paused(env: &Env, feature: &Symbol) -> bool {
+    env.storage().instance().get(&PauseKey::Paused(feature.clone())).unwrap_or(false)
+}
+
+pub fn when_not_paused(env: &Env, feature: &Symbol) {
+    if is_paused(env, feature) {
+        panic!("Paused");
+    }
+}
+
+pub fn pause(env: &Env, caller: &Address, feature: Symbol) {
+    caller.require_auth();
+    if *caller != access_control::admin(env) && !access_control::has_role(env, &GUARDIAN, caller) {
+        panic!("Caller cannot pause");
+    }
+    env.storage().instance().set(&PauseKey::Paused(feature.clone()), &true);
+    Paused { feature, by: caller.clone() }.publish(env);
+}
+
+pub fn unpause(env: &Env, feature: Symbol) {
+    let admin = access_control::require_admin(env);
+    env.storage().instance().remove(&PauseKey::Paused(feature.clone()));
+    Unpaused { feature, by: admin }.publish(env);
+}
+
+// Pause entrypoints for a host contract, generated here so every contract
+// exposes the same interface:
+//
+//     pausable::pause_entrypoints!(CitadelVault);
+#[macro_export]
+macro_rules! pause_entrypoints {
+    ($contract:ident) => {
+        #[soroban_sdk::contractimpl]
+        impl $contract {
+            // Admin or guardian: stop `feature` until the admin unpauses it
+            pub fn pause(env: soroban_sdk::Env, caller: soroban_sdk::Address, feature: soroban_sdk::Symbol) {
+                $crate::pause(&env, &caller, feature);
+            }
+
+            pub fn unpause(env: soroban_sdk::Env, feature: soroban_sdk::Symbol) {
+                $crate::unpause(&env, feature);
+            }
+
+            pub fn is_paused(env: soroban_sdk::Env, feature: soroban_sdk::Symbol) -> bool {
+                $crate::is_paused(&env, &feature)
+            }
+        }
+    };
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use soroban_sdk::{contract, symbol_short, testutils::Address as _};
+
+    #[contract]
+    struct Host;
+
+    pause_entrypoints!(Host);
+
+    #[test]
+    fn features_pause_independently() {
+        let env = Env::default();
+        env.mock_all_auths();
+        let host = env.register(Host, ());
+        let admin = Address::generate(&env);
+        env.as_contract(&host, || access_control::init(&env, &admin));
+
+        env.as_contract(&host, || {
+            pause(&env, &admin, symbol_short!("raid"));
+            assert!(is_paused(&env, &symbol_short!("raid")));
+            assert!(!is_paused(&env, &symbol_short!("withdraw")));
+        });
+        env.as_contract(&host, || {
+            unpause(&env, symbol_short!("raid"));
+            assert!(!is_paused(&env, &symbol_short!("raid")));
+        });
+    }
+
+    #[test]
+    fn guardian_can_pause() {
+        let env = Env::default();
+        env.mock_all_auths();
+        let host = env.register(Host, ());
+        let admin = Address::generate(&env);
+        let guardian = Address::generate(&env);
+        env.as_contract(&host, || access_control::init(&env, &admin));
+        env.as_contract(&host, || access_control::grant_role(&env, &admin, &GUARDIAN, &guardian));
+
+        env.as_contract(&host, || {
+            pause(&env, &guardian, symbol_short!("raid"));
+            assert!(is_paused(&env, &symbol_short!("raid")));
+        });
+    }
+
+    #[test]
+    #[should_panic(expected = "Caller cannot pause")]
+    fn strangers_cannot_pause() {
+        let env = Env::default();
+        env.mock_all_auths();
+        let host = env.register(Host, ());
+        let admin = Address::generate(&env);
+        let stranger = Address::generate(&env);
+        env.as_contract(&host, || access_control::init(&env, &admin));
+
+        env.as_contract(&host, || pause(&env, &stranger, symbol_short!("raid")));
+    }
+
+    #[test]
+    #[should_panic(expected = "Paused")]
+    fn paused_feature_rejects_calls() {
+        let env = Env::default();
+        env.mock_all_auths();
+        let host = env.register(Host, ());
+        let admin = Address::generate(&env);
+        env.as_contract(&host, || access_control::init(&env, &admin));
+
+        env.as_contract(&host, || {
+            pause(&env, &admin, symbol_short!("raid"));
+            when_not_paused(&env, &symbol_short!("raid"));
+        });
+    }
+
+    #[test]
+    fn entrypoints_reach_the_module() {
+        let env = Env::default();
+        env.mock_all_auths();
+        let host = env.register(Host, ());
+        let client = HostClient::new(&env, &host);
+        let admin = Address::generate(&env);
+        env.as_contract(&host, || access_control::init(&env, &admin));
+
+        client.pause(&admin, &symbol_short!("raid"));
+        assert!(client.is_paused(&symbol_short!("raid")));
+        client.unpause(&symbol_short!("raid"));
+        assert!(!client.is_paused(&symbol_short!("raid")));
+    }
+}
@@ -0,0 +1,20 @@
+use soroban_sdk::{Address, Env};
+use citadel_types::{enforce_cooldown, remaining_cooldown, Action};
+use crate::state_storage::DataKey;
+
+pub fn get_yield_engine(env: &Env) -> Option<Address> {
+    env.storage().instance().get(&DataKey::YieldEngine)
+}
+
+pub fn remaining(env: &Env, user: &Address, action: Action) -> u64 {
+    match get_yield_engine(env) {
+        Some(engine) => remaining_cooldown(env, &engine, user, action),
+        None => 0,
+    }
+}
+
+// Cooldowns only apply once a YieldEngine has been set
+pub fn enforce(env: &Env, user: &Address, action: Action) {
+    let Some(engine) = get_yield_engine(env) else { return };
+    enforce_cooldown(env, &engine, user, action);
+}
@@ -4,27 +4,133 @@ mod vault;
 mod battle;
 mod fitness;
 mod state_storage;
-mod escrow_manager;
-mod contract_engine;
+mod cooldowns;
+mod test;
 
-use soroban_sdk::{contract, contractimpl, contracttype, Address, Env};
-use state_storage::{Base, TroopStats, get_base, set_base, get_troop_stats};
-use contract_engine::{simulate_raid, BattleResult, BattlePhase};
-use escrow_manager::Escrow;
+use soroban_sdk::{contract, contractimpl, contracttype, symbol_short, Address, BytesN, Env, Symbol, Vec};
+use citadel_types::{checked, resolve_raid, Action, Base, BattleResult, EngineError, RaidOutcome};
+use state_storage::{DataKey, get_base, set_base, accrue_resources, spend, migrate_base};
+
+const VERSION: u32 = 1;
+
+// Pausable features
+const DEPOSIT: Symbol = symbol_short!("deposit");
+const HARVEST: Symbol = symbol_short!("harvest");
+const UPGRADE: Symbol = symbol_short!("upgrade");
+const TRAIN: Symbol = symbol_short!("train");
+const RAID: Symbol = symbol_short!("raid");
+
+fn is_shielded(env: &Env, base: &Base) -> bool {
+    env.ledger().timestamp() < base.shield_end_time
+}
+
+// Loads both sides of a raid with resources accrued up to now, so pending yield is raidable
+fn raid_bases(env: &Env, attacker: &Address, defender: &Address, troop_count: u32) -> (Base, Base) {
+    if attacker == defender {
+         panic!("Cannot raid own base");
+    }
+    if troop_count == 0 {
+         panic!("Raid needs at least one troop");
+    }
+    let mut base_attacker = get_base(env, attacker.clone()).expect("Attacker base not found");
+    let mut base_defender = get_base(env, defender.clone()).expect("Defender base not found");
+
+    if is_shielded(env, &base_defender) {
+         panic!("Defender is shielded");
+    }
+    if base_attacker.troop_count < troop_count {
+         panic!("Not enough troops");
+    }
+
+    accrue_resources(env, &mut base_attacker);
+    accrue_resources(env, &mut base_defender);
+    (base_attacker, base_defender)
+}
+
+// What `preview_raid` found: the outcome, or why `raid` would be refused right now
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub enum RaidPreview {
+    Allowed(RaidOutcome),
+    Paused,
+    OnCooldown,
+    Shielded,
+}
+
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub enum GameChange {
+    YieldEngine(Address),
+    TimelockDelay(u64),
+}
+
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct QueuedChange {
+    pub id: u64,
+    pub eta: u64,
+    pub change: GameChange,
+}
 
 #[contract]
 pub struct YieldRaiders;
 
+access_control::role_entrypoints!(YieldRaiders);
+pausable::pause_entrypoints!(YieldRaiders);
+timelock::timelock_entrypoints!(YieldRaiders, QueuedChange);
+
 #[contractimpl]
 impl YieldRaiders {
-    
+
+    // Runs once at deploy, so nobody can race the deployer for the admin seat
+    pub fn __constructor(env: Env, admin: Address) {
+        access_control::init(&env, &admin);
+    }
+
+    // Admin: swap the contract code in place, storage and bases are kept
+    pub fn upgrade(env: Env, new_wasm_hash: BytesN<32>) {
+        access_control::require_admin(&env);
+        env.deployer().update_current_contract_wasm(new_wasm_hash);
+    }
+
+    // Admin: queue an engine or delay change
+    pub fn propose_change(env: Env, change: GameChange) -> u64 {
+        timelock::queue(&env, change)
+    }
+
+    pub fn execute_change(env: Env, id: u64) {
+        match timelock::execute(&env, id) {
+            // Route raid cooldowns through YieldEngine, which must grant this contract the GAME role
+            GameChange::YieldEngine(engine) => env.storage().instance().set(&DataKey::YieldEngine, &engine),
+            GameChange::TimelockDelay(seconds) => timelock::set_delay(&env, seconds),
+        }
+    }
+
+    pub fn version(_env: Env) -> u32 {
+        VERSION
+    }
+
+    // Admin: rewrite bases stored in an old layout, returns how many were migrated.
+    // Bases are also migrated lazily on their next write, this just gets ahead of it.
+    pub fn migrate_bases(env: Env, users: Vec<Address>) -> u32 {
+        access_control::require_admin(&env);
+        let mut migrated = 0;
+        for user in users.iter() {
+            if migrate_base(&env, user) {
+                migrated += 1;
+            }
+        }
+        migrated
+    }
+
     pub fn initialize(env: Env, user: Address) {
         user.require_auth();
         if get_base(&env, user.clone()).is_none() {
             let base = Base {
                 owner: user.clone(),
                 level: 1,
-                balance: 1000, // Starting balance bonus for demo
+                principal: 0,
+                resources: 1000, // Starting resource bonus for demo
                 wall_hp: 100,
                 max_wall_hp: 100,
                 troop_level: 1,
@@ -38,107 +144,110 @@ impl YieldRaiders {
     }
 
     pub fn deposit(env: Env, user: Address, amount: i128) {
+        pausable::when_not_paused(&env, &DEPOSIT);
         user.require_auth();
+        if amount <= 0 {
+            panic!("Amount must be positive");
+        }
         let mut base = get_base(&env, user.clone()).expect("Base not initialized");
-        base.balance += amount;
+        // Settle yield on the old principal before it changes
+        accrue_resources(&env, &mut base);
+        base.principal = checked(&env, base.principal.checked_add(amount), EngineError::Overflow);
         set_base(&env, user, &base);
     }
+
+    // Settle principal yield into resources, returns the amount accrued
+    pub fn harvest(env: Env, user: Address) -> i128 {
+        pausable::when_not_paused(&env, &HARVEST);
+        user.require_auth();
+        let mut base = get_base(&env, user.clone()).expect("Base not initialized");
+        let accrued = accrue_resources(&env, &mut base);
+        set_base(&env, user, &base);
+        accrued
+    }
     
     pub fn upgrade_base(env: Env, user: Address) {
+        pausable::when_not_paused(&env, &UPGRADE);
         user.require_auth();
         let mut base = get_base(&env, user.clone()).expect("Base not initialized");
         
+        accrue_resources(&env, &mut base);
         let cost = (base.level as i128) * 100;
-        if base.balance < cost {
+        if !spend(&mut base, cost) {
             panic!("Insufficient funds for upgrade");
         }
         
-        base.balance -= cost;
-        base.level += 1;
-        base.max_troops += 50; 
-        base.max_wall_hp += 100;
+        base.level = checked(&env, base.level.checked_add(1), EngineError::Overflow);
+        base.max_troops = checked(&env, base.max_troops.checked_add(50), EngineError::Overflow);
+        base.max_wall_hp = checked(&env, base.max_wall_hp.checked_add(100), EngineError::Overflow);
         base.wall_hp = base.max_wall_hp;
         
         set_base(&env, user, &base);
     }
     
     pub fn create_troops(env: Env, user: Address, amount: u32) {
+        pausable::when_not_paused(&env, &TRAIN);
         user.require_auth();
         let mut base = get_base(&env, user.clone()).expect("Base not initialized");
+        accrue_resources(&env, &mut base);
         
         let cost_per_unit: i128 = 10; 
         let total_cost = (amount as i128) * cost_per_unit;
         
-        if base.balance < total_cost {
-             panic!("Insufficient funds");
-        }
-        if base.troop_count + amount > base.max_troops {
+        let troop_count = checked(&env, base.troop_count.checked_add(amount), EngineError::Overflow);
+        if troop_count > base.max_troops {
              panic!("Garrison full");
         }
+        if !spend(&mut base, total_cost) {
+             panic!("Insufficient funds");
+        }
         
-        base.balance -= total_cost;
-        base.troop_count += amount;
+        base.troop_count = troop_count;
         
         set_base(&env, user, &base);
     }
 
     pub fn raid(env: Env, attacker: Address, defender: Address, troop_count: u32) -> BattleResult {
+        pausable::when_not_paused(&env, &RAID);
         attacker.require_auth();
-        
-        let mut base_attacker = get_base(&env, attacker.clone()).expect("Attacker base not found");
-        let mut base_defender = get_base(&env, defender.clone()).expect("Defender base not found");
-        
-        if env.ledger().timestamp() < base_defender.shield_end_time {
-             panic!("Defender is shielded");
-        }
-        
-        if base_attacker.troop_count < troop_count {
-             panic!("Not enough troops");
-        }
-        
-        let att_stats = get_troop_stats(base_attacker.troop_level);
-        let def_stats = get_troop_stats(base_defender.troop_level);
-        
-        let result = simulate_raid(&env, &base_defender, troop_count, &att_stats, &def_stats);
-        
-        if result.success {
-            let loot_pool = (base_defender.balance * 20) / 100;
-            let (raider_share, fee, _) = Escrow::calculate_payout(&env, result.destruction_percent, loot_pool);
-            
-            base_defender.balance -= (raider_share + fee);
-            base_attacker.balance += raider_share;
-            
-             if result.phase == BattlePhase::Breach {
-                  base_defender.wall_hp = 0; 
-             } else {
-                  base_defender.wall_hp = 0;
-             }
-        } else {
-             let total_potential = (troop_count as u64) * (att_stats.stamina as u64) * (att_stats.damage as u64);
-             let damage = total_potential as u32;
-             if damage >= base_defender.wall_hp {
-                 base_defender.wall_hp = 0;
-             } else {
-                 base_defender.wall_hp -= damage;
-             }
-        }
-        
-        if result.destruction_percent == 100 {
-             base_attacker.troop_count -= troop_count / 10; 
-        } else if result.destruction_percent >= 30 {
-             base_attacker.troop_count -= troop_count / 2;
-        } else {
-             base_attacker.troop_count -= troop_count;
-        }
-        
-        if result.destruction_percent >= 30 {
+
+        let (mut base_attacker, mut base_defender) = raid_bases(&env, &attacker, &defender, troop_count);
+        cooldowns::enforce(&env, &attacker, Action::Raid);
+
+        let outcome = resolve_raid(&env, &base_attacker, &base_defender, troop_count);
+
+        // resolve_raid never takes more than the defender has or the attacker sent
+        let taken = checked(&env, outcome.loot.checked_add(outcome.fee), EngineError::Overflow);
+        base_defender.resources = checked(&env, base_defender.resources.checked_sub(taken), EngineError::Underflow);
+        base_attacker.resources = checked(&env, base_attacker.resources.checked_add(outcome.loot), EngineError::Overflow);
+        base_defender.wall_hp = checked(&env, base_defender.wall_hp.checked_sub(outcome.wall_damage), EngineError::Underflow);
+        base_attacker.troop_count = checked(&env, base_attacker.troop_count.checked_sub(outcome.troops_lost), EngineError::Underflow);
+
+        if outcome.shields_defender {
              base_defender.shield_end_time = env.ledger().timestamp() + 300; 
         }
         
         set_base(&env, attacker, &base_attacker);
         set_base(&env, defender, &base_defender);
         
-        result
+        outcome.battle
+    }
+
+    // What `raid` would do right now, without changing either base. The engine is
+    // deterministic, so this is exactly the outcome barring a change in between.
+    // A pause, a running cooldown or a shield is reported instead of panicking.
+    pub fn preview_raid(env: Env, attacker: Address, defender: Address, troop_count: u32) -> RaidPreview {
+        if pausable::is_paused(&env, &RAID) {
+            return RaidPreview::Paused;
+        }
+        if cooldowns::remaining(&env, &attacker, Action::Raid) > 0 {
+            return RaidPreview::OnCooldown;
+        }
+        if get_base(&env, defender.clone()).is_some_and(|base| is_shielded(&env, &base)) {
+            return RaidPreview::Shielded;
+        }
+        let (base_attacker, base_defender) = raid_bases(&env, &attacker, &defender, troop_count);
+        RaidPreview::Allowed(resolve_raid(&env, &base_attacker, &base_defender, troop_count))
     }
     
      pub fn get_my_base(env: Env, user: Address) -> Option<Base> {
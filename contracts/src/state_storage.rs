@@ -1,48 +1,190 @@
-#![no_std]
 use soroban_sdk::{contracttype, Address, Env};
+use citadel_types::{checked, Base, EngineError};
+use fixed_math::{checked_mul_div, Rounding};
 
+// Layout written before principal and resources were split. Entries in this
+// shape still live under `DataKey::Base` and are upgraded the first time they
+// are read.
 #[contracttype]
 #[derive(Clone, Debug, Eq, PartialEq)]
-pub struct Base {
+pub struct BaseV1 {
     pub owner: Address,
     pub level: u32,
-    pub balance: i128, // XLM stored in escrow
+    pub balance: i128,
     pub wall_hp: u32,
     pub max_wall_hp: u32,
-    pub troop_level: u32, // Defender troop level
-    pub troop_count: u32, // Defender troop count (garrison)
+    pub troop_level: u32,
+    pub troop_count: u32,
     pub max_troops: u32,
     pub last_claim_time: u64,
     pub shield_end_time: u64,
 }
 
+// Every base is stored tagged with its layout, so adding a field means adding
+// a variant and a migration here instead of breaking existing entries
 #[contracttype]
 #[derive(Clone, Debug, Eq, PartialEq)]
-pub struct TroopStats {
-    pub damage: u32,
-    pub stamina: u32,
+pub enum VersionedBase {
+    V1(BaseV1),
+    V2(Base),
 }
 
 #[contracttype]
 pub enum DataKey {
-    Base(Address),
+    YieldEngine, // Cooldown authority, raids are gated on it once set
+    Base(Address), // Legacy BaseV1 entries, read only for migration
     TotalYield, // Platform revenue
     UserTroop(Address), // Attacker troop stats if stored separately
+    VersionedBase(Address), // User -> VersionedBase
 }
 
+// The whole V1 balance becomes principal: it was mostly deposits, and players
+// should not find funds they put in suddenly raidable. Yield starts from the
+// migration, not from the old claim time.
+fn migrate_v1(env: &Env, v1: BaseV1) -> Base {
+    Base {
+        owner: v1.owner,
+        level: v1.level,
+        principal: v1.balance,
+        resources: 0,
+        wall_hp: v1.wall_hp,
+        max_wall_hp: v1.max_wall_hp,
+        troop_level: v1.troop_level,
+        troop_count: v1.troop_count,
+        max_troops: v1.max_troops,
+        last_claim_time: env.ledger().timestamp(),
+        shield_end_time: v1.shield_end_time,
+    }
+}
+
+fn upgrade_base_layout(env: &Env, stored: VersionedBase) -> Base {
+    match stored {
+        VersionedBase::V1(v1) => migrate_v1(env, v1),
+        VersionedBase::V2(base) => base,
+    }
+}
+
+fn get_stored_base(env: &Env, user: &Address) -> Option<VersionedBase> {
+    if let Some(stored) = env.storage().persistent().get(&DataKey::VersionedBase(user.clone())) {
+        return Some(stored);
+    }
+    env.storage().persistent().get(&DataKey::Base(user.clone())).map(VersionedBase::V1)
+}
+
+// Reads any layout as the current one, the upgraded entry is written on the next `set_base`
 pub fn get_base(env: &Env, user: Address) -> Option<Base> {
-    env.storage().persistent().get(&DataKey::Base(user))
+    get_stored_base(env, &user).map(|stored| upgrade_base_layout(env, stored))
 }
 
 pub fn set_base(env: &Env, user: Address, base: &Base) {
-    env.storage().persistent().set(&DataKey::Base(user), base);
+    env.storage().persistent().remove(&DataKey::Base(user.clone()));
+    env.storage().persistent().set(&DataKey::VersionedBase(user), &VersionedBase::V2(base.clone()));
+}
+
+// Rewrites `user`'s base in the current layout, false if there was nothing to migrate
+pub fn migrate_base(env: &Env, user: Address) -> bool {
+    match get_stored_base(env, &user) {
+        Some(VersionedBase::V2(_)) | None => false,
+        Some(stored) => {
+            let base = upgrade_base_layout(env, stored);
+            set_base(env, user, &base);
+            true
+        }
+    }
 }
 
-pub fn get_troop_stats(level: u32) -> TroopStats {
-    match level {
-        1 => TroopStats { damage: 5, stamina: 20 },
-        2 => TroopStats { damage: 10, stamina: 30 },
-        3 => TroopStats { damage: 15, stamina: 45 },
-        _ => TroopStats { damage: 5 + (level * 5), stamina: 20 + (level * 10) },
+const RESOURCE_APY_BPS: i128 = 1000; // 10% of principal per year
+const SECONDS_IN_YEAR: i128 = 31536000;
+
+// Turns principal yield since `last_claim_time` into resources
+pub fn accrue_resources(env: &Env, base: &mut Base) -> i128 {
+    let now = env.ledger().timestamp();
+    if now <= base.last_claim_time {
+        return 0;
+    }
+    let elapsed = (now - base.last_claim_time) as i128;
+    let accrued = checked(
+        env,
+        checked_mul_div(base.principal, RESOURCE_APY_BPS * elapsed, 10000 * SECONDS_IN_YEAR, Rounding::Floor),
+        EngineError::Overflow,
+    );
+    base.resources = checked(env, base.resources.checked_add(accrued), EngineError::Overflow);
+    base.last_claim_time = now;
+    accrued
+}
+
+// Pays `cost` out of resources first, dipping into principal only for the rest
+pub fn spend(base: &mut Base, cost: i128) -> bool {
+    // A sum past i128::MAX covers any cost, so saturating is exact here
+    if base.resources.saturating_add(base.principal) < cost {
+        return false;
+    }
+    let from_resources = if cost > base.resources { base.resources } else { cost };
+    base.resources -= from_resources;
+    base.principal -= cost - from_resources;
+    true
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::YieldRaiders;
+    use soroban_sdk::testutils::{Address as _, Ledger};
+
+    fn legacy_base(owner: &Address) -> BaseV1 {
+        BaseV1 {
+            owner: owner.clone(),
+            level: 2,
+            balance: 1500,
+            wall_hp: 80,
+            max_wall_hp: 200,
+            troop_level: 1,
+            troop_count: 12,
+            max_troops: 150,
+            last_claim_time: 0,
+            shield_end_time: 0,
+        }
+    }
+
+    #[test]
+    fn v1_base_is_migrated_on_read_and_write() {
+        let env = Env::default();
+        env.ledger().set_timestamp(1_000);
+        let contract = env.register(YieldRaiders, (Address::generate(&env),));
+        let user = Address::generate(&env);
+
+        env.as_contract(&contract, || {
+            env.storage().persistent().set(&DataKey::Base(user.clone()), &legacy_base(&user));
+
+            let base = get_base(&env, user.clone()).unwrap();
+            assert_eq!(base.principal, 1500);
+            assert_eq!(base.resources, 0);
+            assert_eq!(base.level, 2);
+            assert_eq!(base.last_claim_time, 1_000);
+
+            set_base(&env, user.clone(), &base);
+            assert!(!env.storage().persistent().has(&DataKey::Base(user.clone())));
+            assert_eq!(get_base(&env, user.clone()), Some(base));
+        });
+    }
+
+    #[test]
+    fn migrate_base_skips_current_and_missing() {
+        let env = Env::default();
+        let contract = env.register(YieldRaiders, (Address::generate(&env),));
+        let user = Address::generate(&env);
+        let stranger = Address::generate(&env);
+
+        env.as_contract(&contract, || {
+            env.storage().persistent().set(&DataKey::Base(user.clone()), &legacy_base(&user));
+
+            assert!(migrate_base(&env, user.clone()));
+            assert!(!migrate_base(&env, user.clone()));
+            assert!(!migrate_base(&env, stranger));
+            assert!(matches!(
+                env.storage().persistent().get(&DataKey::VersionedBase(user.clone())),
+                Some(VersionedBase::V2(_))
+            ));
+        });
     }
 }
@@ -0,0 +1,200 @@
+#![cfg(test)]
+
+use crate::{GameChange, RaidPreview, YieldRaiders, YieldRaidersClient};
+use access_control::{GAME, OPERATOR};
+use citadel_types::EngineError;
+use citadel_vault::{CitadelVault, CitadelVaultClient, VaultChange};
+use soroban_sdk::{symbol_short, testutils::{Address as _, Ledger}, Address, Env, Error};
+use yield_engine::{Action, EngineChange, YieldEngine, YieldEngineClient};
+
+struct Game<'a> {
+    env: Env,
+    game: YieldRaidersClient<'a>,
+    engine: YieldEngineClient<'a>,
+}
+
+// YieldRaiders gated on YieldEngine, which moves yield in CitadelVault. Every
+// link is registered through its contract's timelock.
+fn setup_game<'a>() -> Game<'a> {
+    let env = Env::default();
+    env.mock_all_auths();
+    let admin = Address::generate(&env);
+
+    let sac = env.register_stellar_asset_contract_v2(Address::generate(&env));
+    let vault = CitadelVaultClient::new(&env, &env.register(CitadelVault, (admin.clone(), sac.address())));
+    let engine = YieldEngineClient::new(&env, &env.register(YieldEngine, (admin.clone(), vault.address.clone())));
+    let game = YieldRaidersClient::new(&env, &env.register(YieldRaiders, (admin,)));
+
+    let ids = [
+        vault.propose_change(&VaultChange::GrantRole(OPERATOR, engine.address.clone())),
+        engine.propose_change(&EngineChange::GrantRole(GAME, game.address.clone())),
+        game.propose_change(&GameChange::YieldEngine(engine.address.clone())),
+    ];
+    env.ledger().set_timestamp(env.ledger().timestamp() + timelock::DEFAULT_DELAY);
+    vault.execute_change(&ids[0]);
+    engine.execute_change(&ids[1]);
+    game.execute_change(&ids[2]);
+
+    Game { env, game, engine }
+}
+
+#[test]
+fn preview_matches_raid_and_changes_nothing() {
+    let env = Env::default();
+    env.mock_all_auths();
+    let game = YieldRaidersClient::new(&env, &env.register(YieldRaiders, (Address::generate(&env),)));
+    let attacker = Address::generate(&env);
+    let defender = Address::generate(&env);
+    game.initialize(&attacker);
+    game.initialize(&defender);
+
+    let RaidPreview::Allowed(preview) = game.preview_raid(&attacker, &defender, &10) else {
+        panic!("Raid should be allowed");
+    };
+    assert_eq!(game.get_my_base(&attacker).unwrap().troop_count, 10);
+    assert_eq!(game.get_my_base(&defender).unwrap().resources, 1000);
+
+    let result = game.raid(&attacker, &defender, &10);
+    assert_eq!(result, preview.battle);
+
+    let base_attacker = game.get_my_base(&attacker).unwrap();
+    let base_defender = game.get_my_base(&defender).unwrap();
+    assert_eq!(base_attacker.troop_count, 10 - preview.troops_lost);
+    assert_eq!(base_attacker.resources, 1000 + preview.loot);
+    assert_eq!(base_defender.resources, 1000 - preview.loot - preview.fee);
+    assert_eq!(base_defender.wall_hp, 100 - preview.wall_damage);
+}
+
+#[test]
+fn preview_reports_shielded_defender() {
+    let env = Env::default();
+    env.mock_all_auths();
+    let game = YieldRaidersClient::new(&env, &env.register(YieldRaiders, (Address::generate(&env),)));
+    let attacker = Address::generate(&env);
+    let defender = Address::generate(&env);
+    game.initialize(&attacker);
+    game.initialize(&defender);
+
+    game.raid(&attacker, &defender, &4);
+    assert_eq!(game.preview_raid(&attacker, &defender, &4), RaidPreview::Shielded);
+}
+
+#[test]
+fn preview_reports_pause_and_cooldown() {
+    let g = setup_game();
+    let attacker = Address::generate(&g.env);
+    let first = Address::generate(&g.env);
+    let second = Address::generate(&g.env);
+    g.game.initialize(&attacker);
+    g.game.initialize(&first);
+    g.game.initialize(&second);
+
+    g.game.pause(&g.game.admin(), &symbol_short!("raid"));
+    assert_eq!(g.game.preview_raid(&attacker, &first, &4), RaidPreview::Paused);
+    g.game.unpause(&symbol_short!("raid"));
+
+    g.game.raid(&attacker, &first, &4);
+    assert_eq!(g.game.preview_raid(&attacker, &second, &2), RaidPreview::OnCooldown);
+}
+
+#[test]
+fn troop_overflow_is_an_explicit_error() {
+    let env = Env::default();
+    env.mock_all_auths();
+    let game = YieldRaidersClient::new(&env, &env.register(YieldRaiders, (Address::generate(&env),)));
+    let user = Address::generate(&env);
+    game.initialize(&user);
+
+    let err = game.try_create_troops(&user, &u32::MAX).unwrap_err().unwrap();
+    assert_eq!(err, Error::from_contract_error(EngineError::Overflow as u32));
+}
+
+#[test]
+fn accrual_overflow_is_an_explicit_error() {
+    let env = Env::default();
+    env.mock_all_auths();
+    let game = YieldRaidersClient::new(&env, &env.register(YieldRaiders, (Address::generate(&env),)));
+    let user = Address::generate(&env);
+    game.initialize(&user);
+    game.deposit(&user, &i128::MAX);
+
+    // Twenty years of 10% on i128::MAX principal is twice what an i128 holds
+    env.ledger().set_timestamp(env.ledger().timestamp() + 20 * 31_536_000);
+    let err = game.try_harvest(&user).unwrap_err().unwrap();
+    assert_eq!(err, Error::from_contract_error(EngineError::Overflow as u32));
+}
+
+#[test]
+#[should_panic(expected = "Cooldown Active")]
+fn raid_waits_for_the_engine_cooldown() {
+    let g = setup_game();
+    let attacker = Address::generate(&g.env);
+    let first = Address::generate(&g.env);
+    let second = Address::generate(&g.env);
+    g.game.initialize(&attacker);
+    g.game.initialize(&first);
+    g.game.initialize(&second);
+
+    g.game.raid(&attacker, &first, &4);
+    assert_eq!(g.engine.remaining_cooldown(&attacker, &Action::Raid), 10);
+    g.game.raid(&attacker, &second, &2);
+}
+
+#[test]
+fn unregistered_game_cannot_start_cooldowns() {
+    let g = setup_game();
+    let attacker = Address::generate(&g.env);
+    let defender = Address::generate(&g.env);
+    g.game.initialize(&attacker);
+    g.game.initialize(&defender);
+
+    g.engine.remove_game_contract(&g.game.address);
+    assert!(g.game.try_raid(&attacker, &defender, &4).is_err());
+    assert_eq!(g.engine.remaining_cooldown(&attacker, &Action::Raid), 0);
+}
+
+#[test]
+#[should_panic(expected = "Cannot raid own base")]
+fn cannot_raid_own_base() {
+    let env = Env::default();
+    env.mock_all_auths();
+    let game = YieldRaidersClient::new(&env, &env.register(YieldRaiders, (Address::generate(&env),)));
+    let user = Address::generate(&env);
+    game.initialize(&user);
+    game.deposit(&user, &5_000);
+
+    game.raid(&user, &user, &10);
+}
+
+#[test]
+#[should_panic(expected = "Raid needs at least one troop")]
+fn cannot_raid_without_troops() {
+    let env = Env::default();
+    env.mock_all_auths();
+    let game = YieldRaidersClient::new(&env, &env.register(YieldRaiders, (Address::generate(&env),)));
+    let attacker = Address::generate(&env);
+    let defender = Address::generate(&env);
+    game.initialize(&attacker);
+    game.initialize(&defender);
+
+    game.raid(&attacker, &defender, &0);
+}
+
+#[test]
+fn raids_take_resources_but_never_principal() {
+    let env = Env::default();
+    env.mock_all_auths();
+    let game = YieldRaidersClient::new(&env, &env.register(YieldRaiders, (Address::generate(&env),)));
+    let attacker = Address::generate(&env);
+    let defender = Address::generate(&env);
+    game.initialize(&attacker);
+    game.initialize(&defender);
+    game.deposit(&defender, &5_000);
+
+    // Breached but stopped with no power edge, the raider takes 20% of the base 10% at stake
+    assert_eq!(game.raid(&attacker, &defender, &10).destruction_percent, 30);
+    let base = game.get_my_base(&defender).unwrap();
+    assert_eq!(base.resources, 1_000 - 20 - 2);
+    assert_eq!(base.principal, 5_000);
+    assert_eq!(game.get_my_base(&attacker).unwrap().resources, 1_020);
+}
@@ -0,0 +1,222 @@
+#![no_std]
+// Timelock for admin parameter changes. The admin queues a change, anyone can
+// see it and its ETA through the host contract's views, and it only applies
+// once the delay has passed. The admin or a GUARDIAN can cancel it before then.
+//
+// Each host contract defines its own change enum and applies it on execute,
+// this module only keeps the queue. Hosts expose `propose_change`, which queues
+// a change and returns its id, and `execute_change`, which applies it once
+// `timelock_delay` has passed. `timelock_entrypoints!` adds the rest.
+
+use soroban_sdk::{contractevent, contracttype, Address, Env, TryFromVal, Val, Vec};
+use access_control::GUARDIAN;
+
+pub const DEFAULT_DELAY: u64 = 2 * 86400; // 2 days
+pub const MIN_DELAY: u64 = 3600;          // 1 hour
+pub const MAX_DELAY: u64 = 30 * 86400;    // 30 days
+
+#[contracttype]
+#[derive(Clone)]
+pub enum TimelockKey {
+    Delay,           // Seconds between queueing and execution
+    NextChangeId,
+    QueuedIds,       // Vec<u64> of changes waiting to execute
+    Queued(u64),     // Id -> (eta, change)
+}
+
+#[contractevent(data_format = "single-value")]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct ChangeQueued {
+    #[topic]
+    pub id: u64,
+    pub eta: u64,
+}
+
+#[contractevent(data_format = "single-value")]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct ChangeExecuted {
+    #[topic]
+    pub id: u64,
+    pub by: Address,
+}
+
+#[contractevent(data_format = "single-value")]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct ChangeCancelled {
+    #[topic]
+    pub id: u64,
+    pub by: Address,
+}
+
+pub fn delay(env: &Env) -> u64 {
+    env.storage().instance().get(&TimelockKey::Delay).unwrap_or(DEFAULT_DELAY)
+}
+
+// Only reachable through an executed change, so the delay can't be skipped
+pub fn set_delay(env: &Env, seconds: u64) {
+    if !(MIN_DELAY..=MAX_DELAY).contains(&seconds) {
+        panic!("Invalid timelock delay");
+    }
+    env.storage().instance().set(&TimelockKey::Delay, &seconds);
+}
+
+pub fn queued_ids(env: &Env) -> Vec<u64> {
+    env.storage().instance().get(&TimelockKey::QueuedIds).unwrap_or(Vec::new(env))
+}
+
+// (eta, change) of a queued change
+pub fn get<T: TryFromVal<Env, Val>>(env: &Env, id: u64) -> Option<(u64, T)> {
+    env.storage().instance().get(&TimelockKey::Queued(id))
+}
+
+fn remove(env: &Env, id: u64) {
+    env.storage().instance().remove(&TimelockKey::Queued(id));
+    let mut ids = queued_ids(env);
+    if let Some(i) = ids.first_index_of(id) {
+        ids.remove(i);
+    }
+    env.storage().instance().set(&TimelockKey::QueuedIds, &ids);
+}
+
+// Admin: returns the id to execute or cancel the change with
+pub fn queue<T>(env: &Env, change: T) -> u64
+where
+    Val: TryFromVal<Env, T>,
+{
+    access_control::require_admin(env);
+    let id: u64 = env.storage().instance().get(&TimelockKey::NextChangeId).unwrap_or(0);
+    let eta = env.ledger().timestamp() + delay(env);
+
+    env.storage().instance().set(&TimelockKey::NextChangeId, &(id + 1));
+    env.storage().instance().set(&TimelockKey::Queued(id), &(eta, change));
+    let mut ids = queued_ids(env);
+    ids.push_back(id);
+    env.storage().instance().set(&TimelockKey::QueuedIds, &ids);
+
+    ChangeQueued { id, eta }.publish(env);
+    id
+}
+
+// Admin: dequeues a change whose ETA has passed, the caller applies it
+pub fn execute<T: TryFromVal<Env, Val>>(env: &Env, id: u64) -> T {
+    let admin = access_control::require_admin(env);
+    let (eta, change): (u64, T) = get(env, id).expect("Change not queued");
+    if env.ledger().timestamp() < eta {
+        panic!("Timelock not expired");
+    }
+    remove(env, id);
+    ChangeExecuted { id, by: admin }.publish(env);
+    change
+}
+
+// Admin or guardian: drop a queued change
+pub fn cancel(env: &Env, caller: &Address, id: u64) {
+    caller.require_auth();
+    if *caller != access_control::admin(env) && !access_control::has_role(env, &GUARDIAN, caller) {
+        panic!("Caller cannot cancel");
+    }
+    if !env.storage().instance().has(&TimelockKey::Queued(id)) {
+        panic!("Change not queued");
+    }
+    remove(env, id);
+    ChangeCancelled { id, by: caller.clone() }.publish(env);
+}
+
+// Queue entrypoints for a host contract. `$queued` is the host's
+// `{ id, eta, change }` view type for its own change enum:
+//
+//     timelock::timelock_entrypoints!(CitadelVault, QueuedChange);
+#[macro_export]
+macro_rules! timelock_entrypoints {
+    ($contract:ident, $queued:ident) => {
+        #[soroban_sdk::contractimpl]
+        impl $contract {
+            // Admin or guardian: drop a queued change
+            pub fn cancel_change(env: soroban_sdk::Env, caller: soroban_sdk::Address, id: u64) {
+                $crate::cancel(&env, &caller, id);
+            }
+
+            pub fn queued_changes(env: soroban_sdk::Env) -> soroban_sdk::Vec<$queued> {
+                let mut queued = soroban_sdk::Vec::new(&env);
+                for id in $crate::queued_ids(&env).iter() {
+                    let (eta, change) = $crate::get(&env, id).expect("Change not queued");
+                    queued.push_back($queued { id, eta, change });
+                }
+                queued
+            }
+
+            pub fn timelock_delay(env: soroban_sdk::Env) -> u64 {
+                $crate::delay(&env)
+            }
+        }
+    };
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use soroban_sdk::{contract, testutils::{Address as _, Ledger}};
+
+    #[contract]
+    struct Host;
+
+    fn setup() -> (Env, Address, Address) {
+        let env = Env::default();
+        env.mock_all_auths();
+        let host = env.register(Host, ());
+        let admin = Address::generate(&env);
+        env.as_contract(&host, || access_control::init(&env, &admin));
+        (env, host, admin)
+    }
+
+    #[test]
+    fn change_executes_after_delay() {
+        let (env, host, _) = setup();
+        env.ledger().set_timestamp(1_000);
+
+        let id = env.as_contract(&host, || queue(&env, 500u32));
+        env.as_contract(&host, || {
+            assert_eq!(get::<u32>(&env, id), Some((1_000 + DEFAULT_DELAY, 500)));
+            assert_eq!(queued_ids(&env).len(), 1);
+        });
+
+        env.ledger().set_timestamp(1_000 + DEFAULT_DELAY);
+        let change: u32 = env.as_contract(&host, || execute(&env, id));
+        assert_eq!(change, 500);
+        env.as_contract(&host, || assert!(queued_ids(&env).is_empty()));
+    }
+
+    #[test]
+    #[should_panic(expected = "Timelock not expired")]
+    fn change_waits_for_eta() {
+        let (env, host, _) = setup();
+        let id = env.as_contract(&host, || queue(&env, 500u32));
+
+        env.ledger().set_timestamp(DEFAULT_DELAY - 1);
+        env.as_contract(&host, || execute::<u32>(&env, id));
+    }
+
+    #[test]
+    #[should_panic(expected = "Change not queued")]
+    fn guardian_cancel_drops_change() {
+        let (env, host, admin) = setup();
+        let guardian = Address::generate(&env);
+        env.as_contract(&host, || access_control::grant_role(&env, &admin, &GUARDIAN, &guardian));
+
+        let id = env.as_contract(&host, || queue(&env, 500u32));
+        env.as_contract(&host, || cancel(&env, &guardian, id));
+
+        env.ledger().set_timestamp(DEFAULT_DELAY);
+        env.as_contract(&host, || execute::<u32>(&env, id));
+    }
+
+    #[test]
+    #[should_panic(expected = "Caller cannot cancel")]
+    fn strangers_cannot_cancel() {
+        let (env, host, _) = setup();
+        let stranger = Address::generate(&env);
+
+        let id = env.as_contract(&host, || queue(&env, 500u32));
+        env.as_contract(&host, || cancel(&env, &stranger, id));
+    }
+}
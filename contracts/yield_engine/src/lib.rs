@@ -1,47 +1,174 @@
 #![no_std]
-use soroban_sdk::{contract, contractimpl, contracttype, Address, Env};
+use soroban_sdk::{contract, contractclient, contractevent, contractimpl, contracttype, Address, BytesN, Env, Symbol};
+use access_control::GAME;
+pub use citadel_types::Action;
+
+mod test;
 
 #[contracttype]
 #[derive(Clone)]
 pub enum DataKey {
-    Cooldown(Address), // User -> EndTimestamp
+    Vault,                      // CitadelVault holding the yield balances
+    Cooldown(Address, Action),  // (User, Action) -> EndTimestamp, temporary storage
+    CooldownDuration(Action),   // Action -> Seconds
+}
+
+fn default_duration(action: Action) -> u64 {
+    match action {
+        Action::Raid => 10,
+        Action::Train => 30,
+        Action::Upgrade => 60,
+        Action::Scout => 5,
+    }
+}
+
+fn cooldown_duration(env: &Env, action: Action) -> u64 {
+    env.storage().instance().get(&DataKey::CooldownDuration(action)).unwrap_or(default_duration(action))
+}
+
+fn cooldown_end(env: &Env, user: Address, action: Action) -> u64 {
+    env.storage().temporary().get(&DataKey::Cooldown(user, action)).unwrap_or(0)
+}
+
+// The slice of CitadelVault this engine calls into
+#[contractclient(name = "CitadelVaultClient")]
+pub trait CitadelVaultInterface {
+    fn transfer_yield(env: Env, operator: Address, from: Address, to: Address, amount: i128) -> i128;
+}
+
+#[contractevent(data_format = "single-value")]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct YieldRaided {
+    #[topic]
+    pub attacker: Address,
+    #[topic]
+    pub victim: Address,
+    pub amount: i128,
+}
+
+const VERSION: u32 = 1;
+
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub enum EngineChange {
+    CooldownDuration(Action, u64),
+    GrantRole(Symbol, Address),  // For GAME, which can move any user's yield
+    TimelockDelay(u64),
+}
+
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct QueuedChange {
+    pub id: u64,
+    pub eta: u64,
+    pub change: EngineChange,
 }
 
 #[contract]
 pub struct YieldEngine;
 
+access_control::role_entrypoints!(YieldEngine);
+timelock::timelock_entrypoints!(YieldEngine, QueuedChange);
+
+fn require_game_contract(env: &Env, game: &Address) {
+    game.require_auth();
+    if !access_control::has_role(env, &GAME, game) {
+        panic!("YieldEngine: Caller is not a registered game contract");
+    }
+}
+
 #[contractimpl]
 impl YieldEngine {
-    
+
+    pub fn __constructor(env: Env, admin: Address, vault: Address) {
+        access_control::init(&env, &admin);
+        env.storage().instance().set(&DataKey::Vault, &vault);
+    }
+
+    // Admin: swap the contract code in place, game roles and cooldowns are kept
+    pub fn upgrade(env: Env, new_wasm_hash: BytesN<32>) {
+        access_control::require_admin(&env);
+        env.deployer().update_current_contract_wasm(new_wasm_hash);
+    }
+
+    pub fn version(_env: Env) -> u32 {
+        VERSION
+    }
+
+    // Admin: queue a cooldown, role or delay change
+    pub fn propose_change(env: Env, change: EngineChange) -> u64 {
+        timelock::queue(&env, change)
+    }
+
+    pub fn execute_change(env: Env, id: u64) {
+        match timelock::execute(&env, id) {
+            // Running cooldowns keep the end time they were started with
+            EngineChange::CooldownDuration(action, seconds) => {
+                env.storage().instance().set(&DataKey::CooldownDuration(action), &seconds)
+            }
+            EngineChange::GrantRole(role, account) => access_control::grant_queued_role(&env, &role, &account),
+            EngineChange::TimelockDelay(seconds) => timelock::set_delay(&env, seconds),
+        }
+    }
+
+    // Admin: shorthands for queueing a GAME grant and revoking it, the grant
+    // returns the change id to execute
+    pub fn add_game_contract(env: Env, game: Address) -> u64 {
+        timelock::queue(&env, EngineChange::GrantRole(GAME, game))
+    }
+
+    pub fn remove_game_contract(env: Env, game: Address) {
+        access_control::revoke_role(&env, &access_control::admin(&env), &GAME, &game);
+    }
+
+    pub fn is_game_contract(env: Env, game: Address) -> bool {
+        access_control::has_role(&env, &GAME, &game)
+    }
+
+    pub fn get_cooldown_duration(env: Env, action: Action) -> u64 {
+        cooldown_duration(&env, action)
+    }
+
+    // Seconds until `action` is available again, 0 when ready
+    pub fn remaining_cooldown(env: Env, user: Address, action: Action) -> u64 {
+        let end_ts = cooldown_end(&env, user, action);
+        end_ts.saturating_sub(env.ledger().timestamp())
+    }
+
     // Check and Enforce Cooldown
-    pub fn check_cooldown(env: Env, user: Address) {
-        let key = DataKey::Cooldown(user.clone());
-        let end_ts: u64 = env.storage().persistent().get(&key).unwrap_or(0);
-        let now = env.ledger().timestamp();
-        
-        if now < end_ts {
+    pub fn check_cooldown(env: Env, user: Address, action: Action) {
+        if cooldown_end(&env, user, action) > env.ledger().timestamp() {
             panic!("YieldEngine: Cooldown Active! Army is Resting.");
         }
     }
-    
-    // Trigger Cooldown
-    pub fn trigger_cooldown(env: Env, user: Address) {
-        user.require_auth();
-        let key = DataKey::Cooldown(user.clone());
-        // 10 seconds cooldown
-        let now = env.ledger().timestamp();
-        let end_ts = now + 10; 
-        env.storage().persistent().set(&key, &end_ts);
-    }
-
-    // Redistribute Yield (Simulation of Logic)
-    // In a real integration, this would call CitadelVault to move yield
-    pub fn redistribute_yield(env: Env, _attacker: Address, _victim: Address, amount: i128) {
-        // Logic to move yield from victim's Yield balance to attacker's Yield balance
-        // Simplified for this scope: Just emitting event or assume cross-contract call
-        env.events().publish(
-            (_attacker, _victim), 
-            amount
-        );
+
+    // Trigger Cooldown, only registered game contracts may start one
+    pub fn trigger_cooldown(env: Env, game: Address, user: Address, action: Action) {
+        require_game_contract(&env, &game);
+        let duration = cooldown_duration(&env, action);
+        if duration == 0 { return; }
+
+        let key = DataKey::Cooldown(user, action);
+        let end_ts = env.ledger().timestamp() + duration;
+        env.storage().temporary().set(&key, &end_ts);
+
+        // Ledgers close at most once a second, so `duration` ledgers always
+        // outlive the cooldown and the entry then expires on its own
+        let max_ttl = env.storage().max_ttl();
+        let ttl = if duration > max_ttl as u64 { max_ttl } else { duration as u32 };
+        env.storage().temporary().extend_ttl(&key, ttl, ttl);
+    }
+
+    // Redistribute Yield: moves accrued yield (never principal) from the victim to
+    // the attacker in CitadelVault. Only a registered game contract may call this.
+    // Returns the amount moved, which is capped at the victim's accrued yield.
+    pub fn redistribute_yield(env: Env, game: Address, attacker: Address, victim: Address, amount: i128) -> i128 {
+        require_game_contract(&env, &game);
+
+        let vault: Address = env.storage().instance().get(&DataKey::Vault).expect("Engine not initialized");
+        let moved = CitadelVaultClient::new(&env, &vault).transfer_yield(&env.current_contract_address(), &victim, &attacker, &amount);
+
+        YieldRaided { attacker, victim, amount: moved }.publish(&env);
+        moved
     }
 }
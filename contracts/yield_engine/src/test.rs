@@ -0,0 +1,149 @@
+#![cfg(test)]
+extern crate std;
+
+use crate::{Action, EngineChange, YieldEngine, YieldEngineClient};
+use access_control::{GAME, OPERATOR};
+use citadel_vault::{CitadelVault, CitadelVaultClient, VaultChange};
+use soroban_sdk::{testutils::{Address as _, Ledger}, token, Address, Env};
+
+struct Setup<'a> {
+    env: Env,
+    engine: YieldEngineClient<'a>,
+    vault: CitadelVaultClient<'a>,
+    token_admin: token::StellarAssetClient<'a>,
+    game: Address,
+}
+
+// Queues `change` and executes it once the timelock allows
+fn apply(env: &Env, engine: &YieldEngineClient, change: EngineChange) {
+    let id = engine.propose_change(&change);
+    env.ledger().set_timestamp(env.ledger().timestamp() + engine.timelock_delay());
+    engine.execute_change(&id);
+}
+
+// Engine with one registered game contract, operating a real CitadelVault
+fn setup<'a>() -> Setup<'a> {
+    let env = Env::default();
+    env.mock_all_auths();
+    let admin = Address::generate(&env);
+
+    let sac = env.register_stellar_asset_contract_v2(Address::generate(&env));
+    let token_admin = token::StellarAssetClient::new(&env, &sac.address());
+    let vault = CitadelVaultClient::new(&env, &env.register(CitadelVault, (admin.clone(), sac.address())));
+
+    let engine = YieldEngineClient::new(&env, &env.register(YieldEngine, (admin.clone(), vault.address.clone())));
+    let id = vault.propose_change(&VaultChange::GrantRole(OPERATOR, engine.address.clone()));
+    env.ledger().set_timestamp(env.ledger().timestamp() + vault.timelock_delay());
+    vault.execute_change(&id);
+
+    let game = Address::generate(&env);
+    apply(&env, &engine, EngineChange::GrantRole(GAME, game.clone()));
+
+    Setup { env, engine, vault, token_admin, game }
+}
+
+#[test]
+fn raided_yield_moves_but_principal_stays() {
+    let s = setup();
+    let attacker = Address::generate(&s.env);
+    let victim = Address::generate(&s.env);
+    let funder = Address::generate(&s.env);
+    s.token_admin.mint(&victim, &1_000);
+    s.token_admin.mint(&funder, &1_000);
+    s.vault.fund_reserve(&funder, &1_000);
+    s.vault.deposit(&victim, &1_000);
+
+    // A year at 10%
+    s.env.ledger().set_timestamp(s.env.ledger().timestamp() + 31_536_000);
+    assert_eq!(s.vault.get_yield(&victim), 100);
+
+    assert_eq!(s.engine.redistribute_yield(&s.game, &attacker, &victim, &60), 60);
+    // Capped at what the victim has left
+    assert_eq!(s.engine.redistribute_yield(&s.game, &attacker, &victim, &500), 40);
+
+    assert_eq!(s.vault.get_yield(&attacker), 100);
+    assert_eq!(s.vault.get_yield(&victim), 0);
+    assert_eq!(s.vault.get_balance(&victim), 1_000);
+    assert_eq!(s.vault.get_shares(&victim), 1_000);
+    assert_eq!(s.vault.get_balance(&attacker), 0);
+}
+
+#[test]
+#[should_panic(expected = "Caller is not a registered game contract")]
+fn only_games_redistribute_yield() {
+    let s = setup();
+    let attacker = Address::generate(&s.env);
+    let victim = Address::generate(&s.env);
+    s.engine.redistribute_yield(&attacker, &attacker, &victim, &100);
+}
+
+#[test]
+fn game_registration_waits_out_the_timelock() {
+    let s = setup();
+    let game = Address::generate(&s.env);
+    assert!(s.engine.try_grant_role(&s.engine.admin(), &GAME, &game).is_err());
+
+    let id = s.engine.add_game_contract(&game);
+    assert!(!s.engine.is_game_contract(&game));
+    s.env.ledger().set_timestamp(s.env.ledger().timestamp() + s.engine.timelock_delay());
+    s.engine.execute_change(&id);
+    assert!(s.engine.is_game_contract(&game));
+
+    s.engine.remove_game_contract(&game);
+    assert!(!s.engine.is_game_contract(&game));
+}
+
+#[test]
+fn cooldowns_are_tracked_per_action() {
+    let s = setup();
+    let user = Address::generate(&s.env);
+
+    s.engine.trigger_cooldown(&s.game, &user, &Action::Raid);
+    assert_eq!(s.engine.remaining_cooldown(&user, &Action::Raid), 10);
+    assert_eq!(s.engine.remaining_cooldown(&user, &Action::Train), 0);
+    assert!(s.engine.try_check_cooldown(&user, &Action::Raid).is_err());
+    s.engine.check_cooldown(&user, &Action::Train);
+
+    s.engine.trigger_cooldown(&s.game, &user, &Action::Upgrade);
+    s.env.ledger().set_timestamp(s.env.ledger().timestamp() + 10);
+    assert_eq!(s.engine.remaining_cooldown(&user, &Action::Raid), 0);
+    assert_eq!(s.engine.remaining_cooldown(&user, &Action::Upgrade), 50);
+    s.engine.check_cooldown(&user, &Action::Raid);
+}
+
+#[test]
+fn configured_durations_apply_to_new_cooldowns() {
+    let s = setup();
+    let user = Address::generate(&s.env);
+    apply(&s.env, &s.engine, EngineChange::CooldownDuration(Action::Scout, 120));
+
+    assert_eq!(s.engine.get_cooldown_duration(&Action::Scout), 120);
+    s.engine.trigger_cooldown(&s.game, &user, &Action::Scout);
+    assert_eq!(s.engine.remaining_cooldown(&user, &Action::Scout), 120);
+}
+
+#[test]
+fn zero_duration_means_no_cooldown() {
+    let s = setup();
+    let user = Address::generate(&s.env);
+    apply(&s.env, &s.engine, EngineChange::CooldownDuration(Action::Raid, 0));
+
+    s.engine.trigger_cooldown(&s.game, &user, &Action::Raid);
+    assert_eq!(s.engine.remaining_cooldown(&user, &Action::Raid), 0);
+    s.engine.check_cooldown(&user, &Action::Raid);
+}
+
+#[test]
+fn only_the_admin_sets_durations() {
+    let s = setup();
+    s.env.set_auths(&[]);
+    assert!(s.engine.try_propose_change(&EngineChange::CooldownDuration(Action::Raid, 0)).is_err());
+}
+
+#[test]
+#[should_panic(expected = "Caller is not a registered game contract")]
+fn only_games_trigger_cooldowns() {
+    let s = setup();
+    let user = Address::generate(&s.env);
+    s.engine.trigger_cooldown(&user, &user, &Action::Raid);
+}